@@ -0,0 +1,100 @@
+//! Call auction equilibrium price.
+
+use crate::{
+    numeric::{PriceValue, QuantityValue},
+    order::Order,
+    types::{OrdType, Price, Quantity, Side},
+};
+
+/// What the auction needs to know about one price level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuctionLevel<P = Price> {
+    pub price: P,
+    /// Total remaining quantity at the level
+    pub quantity: u128,
+    /// Whether a limit order rests at the level, making its price a candidate
+    pub has_limit_orders: bool,
+}
+
+impl<P: PriceValue> AuctionLevel<P> {
+    /// Summarise the orders resting at `price`
+    pub fn from_orders<'a, Q: QuantityValue>(price: P, orders: impl Iterator<Item = &'a Order<P, Q>>) -> Self {
+        let mut level: AuctionLevel<P> = AuctionLevel {
+            price,
+            quantity: 0,
+            has_limit_orders: false,
+        };
+        for order in orders {
+            level.quantity += order.get_remaining_quantity().to_u128();
+            level.has_limit_orders |= !is_auction_market_order(order);
+        }
+        level
+    }
+}
+
+/// Single price an auction would uncross at, with the volume it executes
+/// and what is left over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuctionUncross<P = Price, Q = Quantity> {
+    pub price: P,
+    pub matched_quantity: Q,
+    /// Unmatched quantity at `price` on the heavier side
+    pub imbalance_quantity: Q,
+    /// Side with quantity left over, None when the auction is balanced
+    pub imbalance_side: Option<Side>,
+}
+
+/// Whether an order is a market order waiting in an auction. These trade at
+/// any price, so their resting price is never a candidate.
+pub fn is_auction_market_order<P: PriceValue, Q: QuantityValue>(order: &Order<P, Q>) -> bool {
+    matches!(order.get_ord_type(), OrdType::Market | OrdType::MarketToLimit)
+}
+
+/// Find the price that uncrosses `bids` against `asks`, given level by level.
+///
+/// Candidates are the limit prices on either side and the reference price.
+/// The winner executes the most volume, then leaves the smallest imbalance,
+/// then lies closest to `reference_price` (the lower price on a tie or when
+/// there is no reference). Returns None when nothing would execute.
+pub fn equilibrium<P: PriceValue, Q: QuantityValue>(bids: &[AuctionLevel<P>], asks: &[AuctionLevel<P>], reference_price: Option<P>) -> Option<AuctionUncross<P, Q>> {
+    let mut candidates: Vec<P> = bids.iter()
+        .chain(asks.iter())
+        .filter(|level: &&AuctionLevel<P>| level.has_limit_orders)
+        .map(|level: &AuctionLevel<P>| level.price)
+        .chain(reference_price)
+        .collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    let mut best: Option<(u128, u128, u128, P, Option<Side>)> = None;
+    for price in candidates {
+        let demand: u128 = bids.iter().filter(|level: &&AuctionLevel<P>| level.price >= price).map(|level: &AuctionLevel<P>| level.quantity).sum();
+        let supply: u128 = asks.iter().filter(|level: &&AuctionLevel<P>| level.price <= price).map(|level: &AuctionLevel<P>| level.quantity).sum();
+        let matched: u128 = demand.min(supply);
+        if matched == 0 {
+            continue;
+        }
+
+        let imbalance: u128 = demand.abs_diff(supply);
+        let distance: u128 = reference_price.map_or(0, |reference: P| reference.to_i128().abs_diff(price.to_i128()));
+        let better: bool = best.is_none_or(|(best_matched, best_imbalance, best_distance, _, _)| {
+            (matched, std::cmp::Reverse(imbalance), std::cmp::Reverse(distance))
+                > (best_matched, std::cmp::Reverse(best_imbalance), std::cmp::Reverse(best_distance))
+        });
+        if better {
+            let imbalance_side: Option<Side> = match demand.cmp(&supply) {
+                std::cmp::Ordering::Greater => Some(Side::Buy),
+                std::cmp::Ordering::Less => Some(Side::Sell),
+                std::cmp::Ordering::Equal => None,
+            };
+            best = Some((matched, imbalance, distance, price, imbalance_side));
+        }
+    }
+
+    best.map(|(matched, imbalance, _, price, imbalance_side)| AuctionUncross {
+        price,
+        matched_quantity: Q::saturating_from_u128(matched),
+        imbalance_quantity: Q::saturating_from_u128(imbalance),
+        imbalance_side,
+    })
+}
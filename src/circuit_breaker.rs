@@ -0,0 +1,54 @@
+//! Price bands that pause trading instead of letting it run away.
+
+use crate::{
+    numeric::PriceValue,
+    types::Price,
+};
+
+/// What the book does when an execution would fall outside the band
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreachAction {
+    /// Halt trading; only cancels are accepted until the session resumes
+    Halt,
+    /// Stop matching and collect orders in a call auction until it is uncrossed
+    VolatilityAuction,
+}
+
+/// Band around a reference price that executions must stay within
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceBand<P = Price> {
+    /// Half width of the band in basis points of the reference price
+    pub basis_points: u32,
+    pub action: BreachAction,
+    /// Fixed reference price; None follows the last trade price
+    pub reference_price: Option<P>,
+}
+
+impl<P: PriceValue> PriceBand<P> {
+    pub fn new(basis_points: u32, action: BreachAction) -> Self {
+        Self {
+            basis_points,
+            action,
+            reference_price: None,
+        }
+    }
+
+    pub fn with_reference_price(mut self, reference_price: P) -> Self {
+        self.reference_price = Some(reference_price);
+        self
+    }
+
+    /// Lowest and highest prices allowed around `reference_price`. The band
+    /// is sized on the magnitude of the reference, so it works for negative
+    /// prices too.
+    pub fn limits(&self, reference_price: P) -> (P, P) {
+        let reference: i128 = reference_price.to_i128();
+        let distance: i128 = (reference.unsigned_abs() * self.basis_points as u128 / 10_000) as i128;
+        (P::saturating_from_i128(reference - distance), P::saturating_from_i128(reference + distance))
+    }
+
+    pub fn contains(&self, reference_price: P, price: P) -> bool {
+        let (low, high) = self.limits(reference_price);
+        (low..=high).contains(&price)
+    }
+}
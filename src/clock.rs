@@ -0,0 +1,98 @@
+//! Time sources for the order book.
+
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::types::Timestamp;
+
+/// Nanoseconds in one second
+pub const NANOS_PER_SECOND: Timestamp = 1_000_000_000;
+
+/// Nanoseconds in one day
+pub const NANOS_PER_DAY: Timestamp = 24 * 60 * 60 * NANOS_PER_SECOND;
+
+/// Source of the current time, in nanoseconds since the Unix epoch
+pub trait Clock: Debug {
+    fn now(&self) -> Timestamp;
+}
+
+impl Default for Box<dyn Clock> {
+    fn default() -> Self {
+        Box::new(SystemClock)
+    }
+}
+
+/// Wall clock time
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Timestamp {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as Timestamp)
+    }
+}
+
+/// Deterministic clock that only moves when told to.
+///
+/// Clones share the same time, so a test can keep a handle while the order
+/// book owns another.
+#[derive(Debug, Default, Clone)]
+pub struct ManualClock {
+    now: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    pub fn new(now: Timestamp) -> Self {
+        Self {
+            now: Arc::new(AtomicU64::new(now)),
+        }
+    }
+
+    pub fn set(&self, now: Timestamp) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, by: Timestamp) {
+        self.now.fetch_add(by, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Timestamp {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
+/// Daily market close, as a time of day in UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarketSchedule {
+    pub close_time_of_day: Timestamp,
+}
+
+impl MarketSchedule {
+    /// Close `close_time_of_day` nanoseconds after midnight UTC
+    pub fn new(close_time_of_day: Timestamp) -> Self {
+        Self {
+            close_time_of_day: close_time_of_day % NANOS_PER_DAY,
+        }
+    }
+
+    /// Close at `hour`:`minute` UTC
+    pub fn daily_close(hour: u64, minute: u64) -> Self {
+        Self::new((hour * 60 + minute) * 60 * NANOS_PER_SECOND)
+    }
+
+    /// First market close strictly after `now`
+    pub fn next_close_after(&self, now: Timestamp) -> Timestamp {
+        let start_of_day: Timestamp = now - now % NANOS_PER_DAY;
+        let close_today: Timestamp = start_of_day + self.close_time_of_day;
+        if close_today > now {
+            close_today
+        } else {
+            close_today + NANOS_PER_DAY
+        }
+    }
+}
@@ -0,0 +1,123 @@
+//! Aggregated depth of one side of the book.
+
+use std::collections::BTreeMap;
+use crate::{
+    numeric::{PriceValue, QuantityValue},
+    types::{Price, Quantity, Side},
+};
+
+/// Total resting quantity and number of orders at one price
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepthLevel<Q = Quantity> {
+    pub quantity: Q,
+    pub count: usize,
+}
+
+/// Resting quantity of one side of the book, aggregated per price level and
+/// kept in price order
+#[derive(Debug, Clone)]
+pub struct Depth<P = Price, Q = Quantity> {
+    side: Side,
+    levels: BTreeMap<P, DepthLevel<Q>>,
+}
+
+impl<P: PriceValue, Q: QuantityValue> Depth<P, Q> {
+    pub fn new(side: Side) -> Self {
+        Self {
+            side,
+            levels: BTreeMap::new(),
+        }
+    }
+
+    pub fn get_side(&self) -> Side {
+        self.side
+    }
+
+    /// Number of price levels
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    pub fn get_level(&self, price: P) -> Option<&DepthLevel<Q>> {
+        self.levels.get(&price)
+    }
+
+    /// Levels from the best price outwards
+    pub fn iter(&self) -> Box<dyn Iterator<Item = (P, &DepthLevel<Q>)> + '_> {
+        let levels = self.levels.iter().map(Self::entry);
+        match self.side {
+            Side::Buy => Box::new(levels.rev()),
+            Side::Sell => Box::new(levels),
+        }
+    }
+
+    /// Levels an opposite order limited at `limit_price` can trade with, from
+    /// the best price outwards
+    pub fn iter_up_to(&self, limit_price: P) -> Box<dyn Iterator<Item = (P, &DepthLevel<Q>)> + '_> {
+        match self.side {
+            Side::Buy => Box::new(self.levels.range(limit_price..).rev().map(Self::entry)),
+            Side::Sell => Box::new(self.levels.range(..=limit_price).map(Self::entry)),
+        }
+    }
+
+    /// Quantity an opposite order limited at `limit_price` can trade with,
+    /// saturating at the largest quantity
+    pub fn get_liquidity_up_to(&self, limit_price: P) -> Q {
+        self.iter_up_to(limit_price)
+            .fold(Q::ZERO, |total: Q, (_, level): (P, &DepthLevel<Q>)| total.saturating_add(level.quantity))
+    }
+
+    /// Whether an opposite order limited at `limit_price` can trade its whole
+    /// `quantity`. Walks levels in price order and stops once it is covered.
+    pub fn can_fill(&self, limit_price: P, quantity: Q) -> bool {
+        let needed: u128 = quantity.to_u128();
+        let mut available: u128 = 0;
+        for (_, level) in self.iter_up_to(limit_price) {
+            available += level.quantity.to_u128();
+            if available >= needed {
+                return true;
+            }
+        }
+        available >= needed
+    }
+
+    /// Whether `quantity` more can rest at `price` without the level total
+    /// overflowing
+    pub fn has_room(&self, price: P, quantity: Q) -> bool {
+        self.levels.get(&price).is_none_or(|level: &DepthLevel<Q>| level.quantity.checked_add(quantity).is_some())
+    }
+
+    /// Count a new order resting at `price`
+    pub fn add_order(&mut self, price: P, quantity: Q) {
+        let level: &mut DepthLevel<Q> = self.levels.entry(price).or_insert(DepthLevel { quantity: Q::ZERO, count: 0 });
+        level.count += 1;
+        level.quantity = level.quantity.saturating_add(quantity);
+    }
+
+    /// Take an order with `quantity` left out of the level at `price`
+    pub fn remove_order(&mut self, price: P, quantity: Q) {
+        let Some(level) = self.levels.get_mut(&price) else {
+            return;
+        };
+        level.count = level.count.saturating_sub(1);
+        level.quantity = level.quantity.saturating_sub(quantity);
+        if level.count == 0 {
+            self.levels.remove(&price);
+        }
+    }
+
+    /// Reduce the level at `price` by `quantity` while its orders stay
+    pub fn reduce(&mut self, price: P, quantity: Q) {
+        if let Some(level) = self.levels.get_mut(&price) {
+            level.quantity = level.quantity.saturating_sub(quantity);
+        }
+    }
+
+    fn entry<'a>((price, level): (&'a P, &'a DepthLevel<Q>)) -> (P, &'a DepthLevel<Q>) {
+        (*price, level)
+    }
+}
@@ -0,0 +1,76 @@
+//! Events reported by the order book.
+
+use crate::{
+    circuit_breaker::BreachAction,
+    numeric::{PriceValue, QuantityValue},
+    order::Order,
+    session::TradingPhase,
+    types::{OrderId, OrderStatus, Price, Quantity, Side, Timestamp},
+};
+
+/// Why the book cancelled an order on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelReason {
+    /// Good For Day order cancelled at market close
+    MarketClose,
+    /// Good Till Date order cancelled at its expiry
+    Expired,
+    /// Market order with nothing to rest at once the auction uncrossed
+    AuctionUnfilled,
+    /// Remainder of an order whose execution would have breached the price band
+    PriceBandBreach,
+    /// Triggered stop whose placement was rejected with the given status
+    StopRejected(OrderStatus),
+}
+
+/// An order the book cancelled on its own
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderCancelled<P = Price, Q = Quantity> {
+    pub order_id: OrderId,
+    pub side: Side,
+    pub price: P,
+    pub remaining_quantity: Q,
+    pub reason: CancelReason,
+    pub timestamp: Timestamp,
+}
+
+impl<P: PriceValue, Q: QuantityValue> OrderCancelled<P, Q> {
+    pub fn new(order: &Order<P, Q>, reason: CancelReason, timestamp: Timestamp) -> Self {
+        Self {
+            order_id: order.get_order_id(),
+            side: order.get_side(),
+            price: order.get_price(),
+            remaining_quantity: order.get_remaining_quantity(),
+            reason,
+            timestamp,
+        }
+    }
+}
+
+/// The trading session moved to a new phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseChanged {
+    pub from: TradingPhase,
+    pub to: TradingPhase,
+    pub timestamp: Timestamp,
+}
+
+/// An order would have traded outside the price band, so the book paused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradingPaused<P = Price> {
+    /// Order whose execution would have breached the band
+    pub order_id: OrderId,
+    /// Price the breaching execution would have happened at
+    pub price: P,
+    pub reference_price: P,
+    pub action: BreachAction,
+    pub timestamp: Timestamp,
+}
+
+/// Events the order book publishes, collected until drained
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookEvent<P = Price, Q = Quantity> {
+    PhaseChanged(PhaseChanged),
+    TradingPaused(TradingPaused<P>),
+    OrderCancelled(OrderCancelled<P, Q>),
+}
@@ -0,0 +1,212 @@
+//! Instrument trading rules: price scale and range, tick size, lot size and
+//! order size limits.
+
+use crate::{
+    numeric::{PriceValue, QuantityValue},
+    order::Order,
+    price::PriceScale,
+    types::{OrdType, OrderStatus, Price, Quantity},
+};
+
+/// Tick size by price. Each band applies from its starting price up to the
+/// next band; band starts should themselves be on the lower band's tick.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TickTable<P = Price> {
+    bands: Vec<(P, P)>,
+}
+
+impl<P: PriceValue> Default for TickTable<P> {
+    fn default() -> Self {
+        Self::fixed(P::ONE)
+    }
+}
+
+impl<P: PriceValue> TickTable<P> {
+    /// The same tick size at every price
+    pub fn fixed(tick_size: P) -> Self {
+        Self::new(vec![(P::MIN, tick_size)])
+    }
+
+    /// Bands of `(from_price, tick_size)`, in raw prices. Prices below the
+    /// first band trade in ticks of 1, and a tick size below 1 is treated as 1.
+    pub fn new(mut bands: Vec<(P, P)>) -> Self {
+        bands.sort_unstable_by_key(|(from_price, _)| *from_price);
+        if bands.first().is_none_or(|(from_price, _)| *from_price > P::MIN) {
+            bands.insert(0, (P::MIN, P::ONE));
+        }
+        for (_, tick_size) in bands.iter_mut() {
+            *tick_size = (*tick_size).max(P::ONE);
+        }
+        Self { bands }
+    }
+
+    /// Tick size in force at `price`
+    pub fn tick_size_at(&self, price: P) -> P {
+        self.bands.iter()
+            .rev()
+            .find(|(from_price, _)| *from_price <= price)
+            .map_or(P::ONE, |(_, tick_size)| *tick_size)
+    }
+
+    pub fn is_on_tick(&self, price: P) -> bool {
+        self.remainder(price) == 0
+    }
+
+    /// Nearest price on tick at or below `price`
+    pub fn round_down(&self, price: P) -> P {
+        P::saturating_from_i128(price.to_i128() - self.remainder(price))
+    }
+
+    /// Nearest price on tick at or above `price`
+    pub fn round_up(&self, price: P) -> Option<P> {
+        let remainder: i128 = self.remainder(price);
+        if remainder == 0 {
+            Some(price)
+        } else {
+            P::from_i128(price.to_i128() + self.tick_size_at(price).to_i128() - remainder)
+        }
+    }
+
+    /// Price one tick below `price`
+    pub fn tick_below(&self, price: P) -> Option<P> {
+        let below: P = P::from_i128(price.to_i128() - 1)?;
+        P::from_i128(price.to_i128() - self.tick_size_at(below).to_i128())
+    }
+
+    /// Price one tick above `price`
+    pub fn tick_above(&self, price: P) -> Option<P> {
+        P::from_i128(price.to_i128() + self.tick_size_at(price).to_i128())
+    }
+
+    /// Distance from `price` down to the nearest price on tick
+    fn remainder(&self, price: P) -> i128 {
+        price.to_i128().rem_euclid(self.tick_size_at(price).to_i128())
+    }
+}
+
+/// Per-book rules every order has to satisfy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstrumentRules<P = Price, Q = Quantity> {
+    /// Decimal places of the raw prices, used to parse and format them
+    pub price_scale: PriceScale,
+    /// Lowest limit price accepted; set it to zero or below for
+    /// instruments that trade at zero or negative prices
+    pub min_price: P,
+    pub max_price: P,
+    pub tick_table: TickTable<P>,
+    /// Quantities, including iceberg display quantities, must be a multiple of this
+    pub lot_size: Q,
+    pub min_quantity: Q,
+    pub max_quantity: Option<Q>,
+}
+
+impl<P: PriceValue, Q: QuantityValue> Default for InstrumentRules<P, Q> {
+    fn default() -> Self {
+        Self {
+            price_scale: PriceScale::default(),
+            min_price: P::ONE,
+            max_price: P::MAX,
+            tick_table: TickTable::default(),
+            lot_size: Q::ONE,
+            min_quantity: Q::ONE,
+            max_quantity: None,
+        }
+    }
+}
+
+impl<P: PriceValue, Q: QuantityValue> InstrumentRules<P, Q> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_price_scale(mut self, price_scale: PriceScale) -> Self {
+        self.price_scale = price_scale;
+        self
+    }
+
+    /// Accept limit prices from `min_price` to `max_price` inclusive
+    pub fn with_price_range(mut self, min_price: P, max_price: P) -> Self {
+        self.min_price = min_price;
+        self.max_price = max_price;
+        self
+    }
+
+    /// Tick size in raw prices at every price
+    pub fn with_tick_size(mut self, tick_size: P) -> Self {
+        self.tick_table = TickTable::fixed(tick_size);
+        self
+    }
+
+    pub fn with_tick_table(mut self, tick_table: TickTable<P>) -> Self {
+        self.tick_table = tick_table;
+        self
+    }
+
+    pub fn with_lot_size(mut self, lot_size: Q) -> Self {
+        self.lot_size = lot_size.max(Q::ONE);
+        self
+    }
+
+    pub fn with_min_quantity(mut self, min_quantity: Q) -> Self {
+        self.min_quantity = min_quantity;
+        self
+    }
+
+    pub fn with_max_quantity(mut self, max_quantity: Q) -> Self {
+        self.max_quantity = Some(max_quantity);
+        self
+    }
+
+    pub fn is_price_in_range(&self, price: P) -> bool {
+        (self.min_price..=self.max_price).contains(&price)
+    }
+
+    /// Parse a decimal string into a raw price at this instrument's scale
+    pub fn parse_price(&self, text: &str) -> Option<P> {
+        self.price_scale.parse(text)
+    }
+
+    /// Format a raw price as a decimal string at this instrument's scale
+    pub fn format_price(&self, price: P) -> String {
+        self.price_scale.format(price)
+    }
+
+    /// The rejection `order` breaks, if any. Only limit prices and stop
+    /// prices are checked; market and pegged orders have no price of their own.
+    pub fn validate(&self, order: &Order<P, Q>) -> Option<OrderStatus> {
+        let quantity: Q = order.get_initial_quantity();
+        if quantity == Q::ZERO {
+            return Some(OrderStatus::RejectedInvalidQuantity);
+        }
+        let display_off_lot: bool = order.get_display_quantity().is_some_and(|display: Q| !display.is_multiple_of(self.lot_size));
+        if !quantity.is_multiple_of(self.lot_size) || display_off_lot {
+            return Some(OrderStatus::RejectedQuantityNotOnLot);
+        }
+        if quantity < self.min_quantity {
+            return Some(OrderStatus::RejectedBelowMinimumQuantity);
+        }
+        if self.max_quantity.is_some_and(|max_quantity: Q| quantity > max_quantity) {
+            return Some(OrderStatus::RejectedAboveMaximumQuantity);
+        }
+
+        let has_limit_price: bool = matches!(order.get_ord_type(), OrdType::Limit | OrdType::StopLimit) && order.get_peg().is_none();
+        if has_limit_price {
+            if !self.is_price_in_range(order.get_price()) {
+                return Some(OrderStatus::RejectedInvalidPrice);
+            }
+            if !self.tick_table.is_on_tick(order.get_price()) {
+                return Some(OrderStatus::RejectedPriceNotOnTick);
+            }
+        }
+        if let Some(stop_price) = order.get_stop_price() {
+            if !self.is_price_in_range(stop_price) {
+                return Some(OrderStatus::RejectedInvalidPrice);
+            }
+            if !self.tick_table.is_on_tick(stop_price) {
+                return Some(OrderStatus::RejectedPriceNotOnTick);
+            }
+        }
+
+        None
+    }
+}
@@ -4,13 +4,45 @@
 //!
 
 pub mod types;
+pub mod auction;
+pub mod circuit_breaker;
+pub mod clock;
+pub mod depth;
+pub mod events;
+pub mod instrument;
+pub mod mass_cancel;
+pub mod matching;
+pub mod numeric;
 pub mod order;
 pub mod order_book;
 pub mod order_modify;
+pub mod order_request;
+pub mod order_slab;
+pub mod peg;
+pub mod price;
+pub mod price_ladder;
+pub mod session;
+pub mod stop_book;
 pub mod trade;
 
-pub use types::{OrderId, OrderIds, Price, Quantity, Side, OrderType, OrderStatus, OrderResult, LevelInfo};
+pub use types::{OrderId, OrderIds, ParticipantId, ClientOrderId, Price, Quantity, Timestamp, Side, OrdType, TimeInForce, PostOnly, TrailAmount, OrderStatus, OrderResult, LevelInfo, CancelRejectReason, CancelledOrder, CancelResult};
+pub use auction::{AuctionLevel, AuctionUncross};
+pub use circuit_breaker::{BreachAction, PriceBand};
+pub use clock::{Clock, SystemClock, ManualClock, MarketSchedule};
+pub use depth::{Depth, DepthLevel};
+pub use events::{BookEvent, CancelReason, OrderCancelled, PhaseChanged, TradingPaused};
+pub use instrument::{InstrumentRules, TickTable};
+pub use mass_cancel::{MassCancel, MassCancelSummary};
+pub use matching::{MatchingPolicy, Fifo, ProRata, ProRataTopOrder, LeadMarketMaker};
+pub use numeric::{PriceValue, QuantityValue};
 pub use order::Order;
 pub use order_book::{OrderBook, OrderbookLevelInfos};
 pub use order_modify::OrderModify;
+pub use order_request::OrderRequest;
+pub use order_slab::{OrderHandle, OrderSlab, PriceLevel};
+pub use peg::{Peg, PegType};
+pub use price::PriceScale;
+pub use price_ladder::{PriceLadder, TreeLadder, ArrayLadder};
+pub use session::TradingPhase;
+pub use stop_book::StopBook;
 pub use trade::{Trade, Trades, TradeInfo};
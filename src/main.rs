@@ -1,24 +1,29 @@
-use my_order_book::{OrderBook, Side, OrderType};
+use my_order_book::{OrderBook, OrderRequest, Side, OrdType, TimeInForce, Trades};
+
+fn describe_trades(trades: &Trades) -> String {
+    if trades.is_empty() {
+        "No trades".to_string()
+    } else {
+        format!("{} trades", trades.len())
+    }
+}
 
 fn main() {
     let mut order_book = OrderBook::new();
 
     // Create non-overlapping orders first (no immediate trades)
-    let result1 = order_book.add_order_with_status(Side::Buy, OrderType::Limit, 100, 10);
-    let result2 = order_book.add_order_with_status(Side::Sell, OrderType::Limit, 105, 8);
+    let result1 = order_book.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let result2 = order_book.add_order_with_status(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 8);
 
     println!("=== Initial Orders ===");
-    println!("Buy order {}: {} - {}", result1.order_id, result1.status.message(),
-             if result1.trades.is_empty() { "No trades" } else { &format!("{} trades", result1.trades.len()) });
-    println!("Sell order {}: {} - {}", result2.order_id, result2.status.message(),
-             if result2.trades.is_empty() { "No trades" } else { &format!("{} trades", result2.trades.len()) });
+    println!("Buy order {}: {} - {}", result1.order_id, result1.status.message(), describe_trades(&result1.trades));
+    println!("Sell order {}: {} - {}", result2.order_id, result2.status.message(), describe_trades(&result2.trades));
 
     // Now add a crossing order that will create trades
     println!("\n=== Adding Crossing Order ===");
-    let result3 = order_book.add_order_with_status(Side::Buy, OrderType::Limit, 106, 5);
+    let result3 = order_book.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 106, 5);
 
-    println!("Crossing buy order {}: {} - {}", result3.order_id, result3.status.message(),
-             if result3.trades.is_empty() { "No trades" } else { &format!("{} trades", result3.trades.len()) });
+    println!("Crossing buy order {}: {} - {}", result3.order_id, result3.status.message(), describe_trades(&result3.trades));
 
     if !result3.trades.is_empty() {
         println!("Trade details: {:?}", result3.trades);
@@ -34,10 +39,19 @@ fn main() {
 
     // Try a market buy with no asks
     let mut empty_book = OrderBook::new();
-    let market_result = empty_book.add_order_with_status(Side::Buy, OrderType::Market, 0, 10);
+    let market_result = empty_book.add_order_with_status(Side::Buy, OrdType::Market, TimeInForce::GoodTillCancel, 0, 10);
     println!("Market buy with no liquidity: {}", market_result.status.message());
 
     // Try Fill-or-Kill that can't be fully filled
-    let fok_result = order_book.add_order_with_status(Side::Buy, OrderType::FillOrKill, 110, 100);
+    let fok_result = order_book.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::FillOrKill, 110, 100);
     println!("Fill-or-Kill for 100 shares: {}", fok_result.status.message());
+
+    // Stop orders wait until the last trade price reaches their stop price
+    println!("\n=== Demonstrating Stop Orders ===");
+    let stop_result = order_book.submit_order(OrderRequest::new(Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 2).with_stop_price(106));
+    println!("Buy stop at 106: {}", stop_result.status.message());
+
+    order_book.add_order_with_status(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 106, 5);
+    let trigger_result = order_book.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 106, 4);
+    println!("Buy 4 at 106 lifts the offers and triggers the stop: {}", describe_trades(&trigger_result.trades));
 }
@@ -0,0 +1,85 @@
+//! Filters for cancelling many orders at once.
+
+use crate::{
+    numeric::{PriceValue, QuantityValue},
+    order::Order,
+    types::{CancelledOrder, OrdType, ParticipantId, Price, Quantity, Side},
+};
+
+/// Which orders a mass cancel removes. Every filter that is set must match;
+/// a filter with nothing set matches every order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MassCancel<P = Price> {
+    pub participant: Option<ParticipantId>,
+    pub side: Option<Side>,
+    /// Inclusive range of limit prices
+    pub price_range: Option<(P, P)>,
+    pub ord_type: Option<OrdType>,
+}
+
+impl<P: PriceValue> MassCancel<P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_participant(mut self, participant: ParticipantId) -> Self {
+        self.participant = Some(participant);
+        self
+    }
+
+    pub fn with_side(mut self, side: Side) -> Self {
+        self.side = Some(side);
+        self
+    }
+
+    /// Only cancel orders priced from `min_price` to `max_price` inclusive
+    pub fn with_price_range(mut self, min_price: P, max_price: P) -> Self {
+        self.price_range = Some((min_price, max_price));
+        self
+    }
+
+    pub fn with_ord_type(mut self, ord_type: OrdType) -> Self {
+        self.ord_type = Some(ord_type);
+        self
+    }
+
+    /// Whether orders on `side` can match
+    pub fn matches_side(&self, side: Side) -> bool {
+        self.side.is_none_or(|filter: Side| filter == side)
+    }
+
+    pub fn matches_price(&self, price: P) -> bool {
+        self.price_range.is_none_or(|(min_price, max_price): (P, P)| min_price <= price && price <= max_price)
+    }
+
+    pub fn matches<Q: QuantityValue>(&self, order: &Order<P, Q>) -> bool {
+        self.matches_side(order.get_side())
+            && self.matches_price(order.get_price())
+            && self.participant.is_none_or(|participant: ParticipantId| order.get_participant() == Some(participant))
+            && self.ord_type.is_none_or(|ord_type: OrdType| order.get_ord_type() == ord_type)
+    }
+}
+
+/// Everything a mass cancel removed
+#[derive(Debug, Clone, PartialEq)]
+pub struct MassCancelSummary<P = Price, Q = Quantity> {
+    /// Cancelled orders in order id order
+    pub cancelled: Vec<CancelledOrder<P, Q>>,
+}
+
+impl<P: PriceValue, Q: QuantityValue> MassCancelSummary<P, Q> {
+    pub fn new(cancelled: Vec<CancelledOrder<P, Q>>) -> Self {
+        Self { cancelled }
+    }
+
+    /// Number of orders cancelled
+    pub fn count(&self) -> usize {
+        self.cancelled.len()
+    }
+
+    /// Total unfilled quantity taken out of the book
+    pub fn get_cancelled_quantity(&self) -> Q {
+        self.cancelled.iter()
+            .fold(Q::ZERO, |total: Q, cancelled: &CancelledOrder<P, Q>| total.saturating_add(cancelled.remaining_quantity))
+    }
+}
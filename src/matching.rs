@@ -0,0 +1,162 @@
+//! How an aggressor's quantity is shared across the resting orders at a level.
+
+use std::fmt::Debug;
+use crate::{
+    numeric::{PriceValue, QuantityValue},
+    order::Order,
+    types::{Price, Quantity},
+};
+
+/// Decides how much of an incoming quantity each resting order at a price
+/// level receives.
+///
+/// `resting` yields the level in time priority. The result has one entry per
+/// resting order, in the same order, and an order is never allocated more
+/// than its visible quantity. Orders past the end of the result receive
+/// nothing, so a policy may stop reading the level once the quantity is
+/// placed. The book asks again once iceberg slices have been replenished,
+/// so a policy only has to share out what is currently displayed.
+pub trait MatchingPolicy<P = Price, Q = Quantity>: Debug {
+    fn allocate(&self, quantity: Q, resting: &mut dyn Iterator<Item = &Order<P, Q>>) -> Vec<Q>;
+}
+
+impl<P: PriceValue, Q: QuantityValue> Default for Box<dyn MatchingPolicy<P, Q>> {
+    fn default() -> Self {
+        Box::new(Fifo)
+    }
+}
+
+/// Price-time priority: the oldest order at the level is filled first
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Fifo;
+
+impl<P: PriceValue, Q: QuantityValue> MatchingPolicy<P, Q> for Fifo {
+    fn allocate(&self, mut quantity: Q, resting: &mut dyn Iterator<Item = &Order<P, Q>>) -> Vec<Q> {
+        let mut allocations: Vec<Q> = Vec::new();
+        for order in resting {
+            if quantity == Q::ZERO {
+                break;
+            }
+            let fill: Q = quantity.min(order.get_visible_quantity());
+            allocations.push(fill);
+            quantity -= fill;
+        }
+        allocations
+    }
+}
+
+/// Every order receives a share proportional to its size, rounded down.
+/// Lots left over by rounding go out in time priority.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProRata;
+
+impl<P: PriceValue, Q: QuantityValue> MatchingPolicy<P, Q> for ProRata {
+    fn allocate(&self, quantity: Q, resting: &mut dyn Iterator<Item = &Order<P, Q>>) -> Vec<Q> {
+        let resting: Vec<&Order<P, Q>> = resting.collect();
+        let mut allocations: Vec<Q> = vec![Q::ZERO; resting.len()];
+        allocate_pro_rata(quantity, &resting, &mut allocations);
+        allocations
+    }
+}
+
+/// The order at the front of the level is filled first, the rest of the
+/// quantity is shared pro-rata
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ProRataTopOrder;
+
+impl<P: PriceValue, Q: QuantityValue> MatchingPolicy<P, Q> for ProRataTopOrder {
+    fn allocate(&self, quantity: Q, resting: &mut dyn Iterator<Item = &Order<P, Q>>) -> Vec<Q> {
+        let resting: Vec<&Order<P, Q>> = resting.collect();
+        let mut allocations: Vec<Q> = vec![Q::ZERO; resting.len()];
+        let Some(top) = resting.first() else {
+            return allocations;
+        };
+        allocations[0] = quantity.min(top.get_visible_quantity());
+        allocate_pro_rata(quantity - allocations[0], &resting, &mut allocations);
+        allocations
+    }
+}
+
+/// Lead market maker orders receive up to `percentage` of the quantity
+/// first, in time priority among themselves; the rest is shared pro-rata
+/// across every order at the level
+#[derive(Debug, Clone, Copy)]
+pub struct LeadMarketMaker {
+    percentage: u32,
+}
+
+impl LeadMarketMaker {
+    /// Percentages above 100 are treated as 100
+    pub fn new(percentage: u32) -> Self {
+        Self {
+            percentage: percentage.min(100),
+        }
+    }
+
+    pub fn get_percentage(&self) -> u32 {
+        self.percentage
+    }
+}
+
+impl<P: PriceValue, Q: QuantityValue> MatchingPolicy<P, Q> for LeadMarketMaker {
+    fn allocate(&self, quantity: Q, resting: &mut dyn Iterator<Item = &Order<P, Q>>) -> Vec<Q> {
+        let resting: Vec<&Order<P, Q>> = resting.collect();
+        let mut allocations: Vec<Q> = vec![Q::ZERO; resting.len()];
+        let mut entitlement: Q = Q::saturating_from_u128(quantity.to_u128() * self.percentage as u128 / 100);
+        let mut remaining: Q = quantity;
+
+        for (order, allocation) in resting.iter().zip(allocations.iter_mut()) {
+            if entitlement == Q::ZERO {
+                break;
+            }
+            if order.is_lead_market_maker() {
+                let fill: Q = entitlement.min(order.get_visible_quantity());
+                *allocation = fill;
+                entitlement -= fill;
+                remaining -= fill;
+            }
+        }
+
+        allocate_pro_rata(remaining, &resting, &mut allocations);
+        allocations
+    }
+}
+
+/// Give out `quantity` in time priority on top of what is already allocated,
+/// returning whatever could not be placed
+fn allocate_fifo<P: PriceValue, Q: QuantityValue>(mut quantity: Q, resting: &[&Order<P, Q>], allocations: &mut [Q]) -> Q {
+    for (order, allocation) in resting.iter().zip(allocations.iter_mut()) {
+        if quantity == Q::ZERO {
+            break;
+        }
+        let fill: Q = quantity.min(order.get_visible_quantity() - *allocation);
+        *allocation += fill;
+        quantity -= fill;
+    }
+    quantity
+}
+
+/// Share out `quantity` in proportion to each order's unallocated size, then
+/// hand out the rounding remainder in time priority
+fn allocate_pro_rata<P: PriceValue, Q: QuantityValue>(quantity: Q, resting: &[&Order<P, Q>], allocations: &mut [Q]) -> Q {
+    let open: Vec<Q> = resting.iter()
+        .zip(allocations.iter())
+        .map(|(order, allocation)| order.get_visible_quantity() - *allocation)
+        .collect();
+    let total: u128 = open.iter().map(|quantity: &Q| quantity.to_u128()).sum();
+
+    if total <= quantity.to_u128() {
+        for (allocation, open) in allocations.iter_mut().zip(open) {
+            *allocation += open;
+        }
+        return Q::saturating_from_u128(quantity.to_u128() - total);
+    }
+
+    let mut remaining: Q = quantity;
+    for (allocation, open) in allocations.iter_mut().zip(open) {
+        let share: Q = Q::saturating_from_u128(quantity.to_u128() * open.to_u128() / total);
+        *allocation += share;
+        remaining -= share;
+    }
+    allocate_fifo(remaining, resting, allocations)
+}
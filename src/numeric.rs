@@ -0,0 +1,105 @@
+//! Numeric types an order book can be built over.
+
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    iter::Sum,
+    ops::{Add, AddAssign, Sub, SubAssign},
+};
+
+/// Integer type usable as a raw price.
+///
+/// Price arithmetic (ticks, bands, pegs, trails) is done in `i128`, which
+/// holds every supported price type exactly.
+pub trait PriceValue: Copy + Ord + Hash + Debug + Default + 'static {
+    const ZERO: Self;
+    const ONE: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    fn to_i128(self) -> i128;
+
+    /// None if `value` is outside the type's range
+    fn from_i128(value: i128) -> Option<Self>;
+
+    /// Convert `value`, clamping it to the type's range
+    fn saturating_from_i128(value: i128) -> Self {
+        Self::from_i128(value).unwrap_or(if value < 0 { Self::MIN } else { Self::MAX })
+    }
+}
+
+/// Unsigned integer type usable as an order quantity
+pub trait QuantityValue:
+    Copy + Ord + Hash + Debug + Default + Add<Output = Self> + Sub<Output = Self> + AddAssign + SubAssign + Sum + 'static
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const MAX: Self;
+
+    fn to_u128(self) -> u128;
+
+    /// None if `value` is outside the type's range
+    fn from_u128(value: u128) -> Option<Self>;
+
+    /// Convert `value`, clamping it to the type's range
+    fn saturating_from_u128(value: u128) -> Self {
+        Self::from_u128(value).unwrap_or(Self::MAX)
+    }
+
+    /// None if the sum does not fit in the type
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Self::from_u128(self.to_u128() + other.to_u128())
+    }
+
+    fn saturating_add(self, other: Self) -> Self {
+        Self::saturating_from_u128(self.to_u128() + other.to_u128())
+    }
+
+    fn saturating_sub(self, other: Self) -> Self {
+        if self > other { self - other } else { Self::ZERO }
+    }
+
+    fn is_multiple_of(self, other: Self) -> bool {
+        self.to_u128().is_multiple_of(other.to_u128())
+    }
+}
+
+macro_rules! impl_price_value {
+    ($($value:ty),*) => {$(
+        impl PriceValue for $value {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MIN: Self = <$value>::MIN;
+            const MAX: Self = <$value>::MAX;
+
+            fn to_i128(self) -> i128 {
+                self as i128
+            }
+
+            fn from_i128(value: i128) -> Option<Self> {
+                Self::try_from(value).ok()
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_quantity_value {
+    ($($value:ty),*) => {$(
+        impl QuantityValue for $value {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const MAX: Self = <$value>::MAX;
+
+            fn to_u128(self) -> u128 {
+                self as u128
+            }
+
+            fn from_u128(value: u128) -> Option<Self> {
+                Self::try_from(value).ok()
+            }
+        }
+    )*};
+}
+
+impl_price_value!(u32, u64, i64);
+impl_quantity_value!(u32, u64);
@@ -1,43 +1,77 @@
-use crate::{OrderId, Price, Quantity, Side, OrderType};
+use crate::{ClientOrderId, OrderId, ParticipantId, Price, Quantity, Side, OrdType, TimeInForce, Peg, PostOnly, Timestamp, TrailAmount};
+use crate::numeric::{PriceValue, QuantityValue};
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Order {
+pub struct Order<P = Price, Q = Quantity> {
     pub id: OrderId,
     pub side: Side,
-    pub order_type: OrderType,
-    pub price: Price,
-    pub quantity: Quantity,
-    pub filled_quantity: Quantity,
+    pub ord_type: OrdType,
+    pub time_in_force: TimeInForce,
+    pub price: P,
+    pub quantity: Q,
+    pub filled_quantity: Q,
+    pub stop_price: Option<P>,
+    pub expiry: Option<Timestamp>,
+    /// Peak size shown for iceberg orders; None shows the full quantity
+    pub display_quantity: Option<Q>,
+    /// Unfilled part of the currently displayed iceberg slice
+    pub visible_quantity: Q,
+    pub post_only: Option<PostOnly>,
+    pub peg: Option<Peg<P>>,
+    pub trail: Option<TrailAmount<P>>,
+    /// Quote from the designated lead market maker, allocated first under
+    /// lead market maker matching
+    pub lead_market_maker: bool,
+    pub participant: Option<ParticipantId>,
+    pub client_order_id: Option<ClientOrderId>,
 }
 
-impl Order {
-    pub fn new(id: OrderId, side: Side, order_type: OrderType, price: Price, quantity: Quantity) -> Self {
+impl<P: PriceValue, Q: QuantityValue> Order<P, Q> {
+    pub fn new(id: OrderId, side: Side, ord_type: OrdType, time_in_force: TimeInForce, price: P, quantity: Q) -> Self {
         Self {
             id,
             side,
-            order_type,
+            ord_type,
+            time_in_force,
             price,
             quantity,
-            filled_quantity: 0,
+            filled_quantity: Q::ZERO,
+            stop_price: None,
+            expiry: None,
+            display_quantity: None,
+            visible_quantity: quantity,
+            post_only: None,
+            peg: None,
+            trail: None,
+            lead_market_maker: false,
+            participant: None,
+            client_order_id: None,
         }
     }
 
-    pub fn fill(&mut self, quantity: Quantity) {
-        self.filled_quantity += quantity;
-        if self.filled_quantity > self.quantity {
-            self.filled_quantity = self.quantity;
-        }
+    /// Fill up to `quantity`; an order is never filled beyond its size
+    pub fn fill(&mut self, quantity: Q) {
+        self.filled_quantity = self.filled_quantity
+            .checked_add(quantity)
+            .map_or(self.quantity, |filled: Q| filled.min(self.quantity));
+        self.visible_quantity = self.visible_quantity.saturating_sub(quantity);
+    }
+
+    /// Change the order's total size, keeping what has already filled
+    pub fn resize(&mut self, quantity: Q) {
+        self.quantity = quantity;
+        self.visible_quantity = self.get_visible_quantity();
     }
 
     pub fn is_filled(&self) -> bool {
         self.filled_quantity >= self.quantity
     }
 
-    pub fn get_remaining_quantity(&self) -> Quantity {
+    pub fn get_remaining_quantity(&self) -> Q {
         self.quantity.saturating_sub(self.filled_quantity)
     }
 
-    pub fn get_initial_quantity(&self) -> Quantity {
+    pub fn get_initial_quantity(&self) -> Q {
         self.quantity
     }
 
@@ -49,19 +83,131 @@ impl Order {
         self.side
     }
 
-    pub fn get_price(&self) -> Price {
+    pub fn get_price(&self) -> P {
         self.price
     }
 
-    pub fn get_order_type(&self) -> OrderType {
-        self.order_type
+    pub fn get_ord_type(&self) -> OrdType {
+        self.ord_type
+    }
+
+    pub fn get_time_in_force(&self) -> TimeInForce {
+        self.time_in_force
+    }
+
+    pub fn get_stop_price(&self) -> Option<P> {
+        self.stop_price
+    }
+
+    /// Expiry timestamp for Good Till Date orders
+    pub fn get_expiry(&self) -> Option<Timestamp> {
+        self.expiry
+    }
+
+    /// Make this an iceberg order showing at most `display_quantity` at a time
+    pub fn set_display_quantity(&mut self, display_quantity: Q) {
+        self.display_quantity = Some(display_quantity);
+        self.visible_quantity = display_quantity.min(self.get_remaining_quantity());
+    }
+
+    pub fn get_display_quantity(&self) -> Option<Q> {
+        self.display_quantity
+    }
+
+    pub fn is_iceberg(&self) -> bool {
+        self.display_quantity.is_some()
+    }
+
+    /// Quantity shown in the book: the current slice for icebergs, the
+    /// remaining quantity otherwise
+    pub fn get_visible_quantity(&self) -> Q {
+        match self.display_quantity {
+            Some(_) => self.visible_quantity.min(self.get_remaining_quantity()),
+            None => self.get_remaining_quantity(),
+        }
+    }
+
+    /// Whether an iceberg has used up its displayed slice but has hidden
+    /// quantity left
+    pub fn needs_replenish(&self) -> bool {
+        self.is_iceberg() && !self.is_filled() && self.visible_quantity == Q::ZERO
+    }
+
+    /// Show the next iceberg slice
+    pub fn replenish(&mut self) {
+        if let Some(display_quantity) = self.display_quantity {
+            self.visible_quantity = display_quantity.min(self.get_remaining_quantity());
+        }
+    }
+
+    pub fn get_post_only(&self) -> Option<PostOnly> {
+        self.post_only
+    }
+
+    pub fn get_peg(&self) -> Option<Peg<P>> {
+        self.peg
+    }
+
+    pub fn get_trail(&self) -> Option<TrailAmount<P>> {
+        self.trail
+    }
+
+    pub fn is_lead_market_maker(&self) -> bool {
+        self.lead_market_maker
+    }
+
+    pub fn get_participant(&self) -> Option<ParticipantId> {
+        self.participant
+    }
+
+    pub fn get_client_order_id(&self) -> Option<ClientOrderId> {
+        self.client_order_id
+    }
+
+    /// Stop price a trailing stop would move to after a trade at
+    /// `last_trade_price`, if that is more favourable than its current stop.
+    /// The stop keeps at least `min_distance` from the trade, so a trail that
+    /// rounds to nothing cannot sit on the last price.
+    pub fn trailing_stop_price(&self, last_trade_price: P, min_distance: P) -> Option<P> {
+        let distance: i128 = self.trail?.distance(last_trade_price).max(min_distance).to_i128();
+        let (candidate, current): (P, Option<P>) = match self.side {
+            Side::Buy => (P::saturating_from_i128(last_trade_price.to_i128() + distance), self.stop_price),
+            Side::Sell => (P::saturating_from_i128(last_trade_price.to_i128() - distance), self.stop_price),
+        };
+
+        match (self.side, current) {
+            (Side::Buy, Some(stop)) if candidate >= stop => None,
+            (Side::Sell, Some(stop)) if candidate <= stop => None,
+            _ => Some(candidate),
+        }
+    }
+
+    /// Whether the order waits in the stop book until its stop price is reached
+    pub fn is_stop(&self) -> bool {
+        matches!(self.ord_type, OrdType::Stop | OrdType::StopLimit)
+    }
+
+    /// Release a triggered stop: Stop becomes Market, StopLimit becomes
+    /// Limit. The time in force is kept.
+    pub fn trigger(&mut self) {
+        self.ord_type = match self.ord_type {
+            OrdType::Stop => OrdType::Market,
+            OrdType::StopLimit => OrdType::Limit,
+            other => other,
+        };
+    }
+
+    /// Move the order to a new price
+    pub fn reprice(&mut self, price: P) {
+        self.price = price;
     }
 
-    /// Convert market order to good till cancel with specified price
-    pub fn to_good_till_cancel(&mut self, price: Price) {
+    /// Convert a market order into a limit order at `price`, keeping its
+    /// time in force
+    pub fn to_limit(&mut self, price: P) {
         self.price = price;
-        self.order_type = OrderType::GoodTillCancel;
+        self.ord_type = OrdType::Limit;
     }
 }
 
-pub type Orders = Vec<Order>;
+pub type Orders<P = Price, Q = Quantity> = Vec<Order<P, Q>>;
@@ -1,41 +1,41 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use crate::{
+    auction::{self, AuctionLevel, AuctionUncross},
+    circuit_breaker::{BreachAction, PriceBand},
+    clock::{Clock, MarketSchedule},
+    depth::Depth,
+    events::{BookEvent, CancelReason, OrderCancelled, PhaseChanged, TradingPaused},
+    instrument::InstrumentRules,
+    mass_cancel::{MassCancel, MassCancelSummary},
+    matching::MatchingPolicy,
+    numeric::{PriceValue, QuantityValue},
     order::{Order, Orders},
+    order_slab::{OrderHandle, OrderSlab, PriceLevel},
     order_modify::OrderModify,
+    order_request::OrderRequest,
+    peg::Peg,
+    price_ladder::{PriceLadder, TreeLadder},
+    session::TradingPhase,
+    stop_book::StopBook,
     trade::{Trade, Trades,  TradeInfo},
-    types::{LevelInfo, OrderId, OrderIds, OrderType, Price, Quantity, Side, OrderStatus, OrderResult},
+    types::{ClientOrderId, LevelInfo, OrderId, ParticipantId, OrderIds, OrdType, TimeInForce, PostOnly, Price, Quantity, Side, OrderStatus, OrderResult, Timestamp, CancelRejectReason, CancelledOrder, CancelResult},
 };
 
-type MatchedTrade = (OrderId, Price, OrderId, Price, Quantity, bool, bool);
-type MatchedTrades = Vec<MatchedTrade>;
+type MatchedTrade<P, Q> = (TradeInfo<P, Q>, TradeInfo<P, Q>, bool, bool);
+type MatchedTrades<P, Q> = Vec<MatchedTrade<P, Q>>;
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct OrderbookLevelInfos {
-    pub bids: Vec<LevelInfo>,
-    pub asks: Vec<LevelInfo>,
+pub struct OrderbookLevelInfos<P = Price, Q = Quantity> {
+    pub bids: Vec<LevelInfo<P, Q>>,
+    pub asks: Vec<LevelInfo<P, Q>>,
 }
 
-impl OrderbookLevelInfos {
-    pub fn new(bids: Vec<LevelInfo>, asks: Vec<LevelInfo>) -> Self {
+impl<P, Q> OrderbookLevelInfos<P, Q> {
+    pub fn new(bids: Vec<LevelInfo<P, Q>>, asks: Vec<LevelInfo<P, Q>>) -> Self {
         Self { bids, asks }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-struct LevelData {
-    quantity: Quantity,
-    count: Quantity,
-}
-
-impl LevelData {
-    fn new() -> Self {
-        Self {
-            quantity: 0,
-            count: 0,
-        }
-    }
-}
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum LevelAction {
     Add,
@@ -43,453 +43,1140 @@ enum LevelAction {
     Match,
 }
 
-#[derive(Debug, Default)]
-pub struct OrderBook {
-    pub bids: BTreeMap<Price, Orders>,
-    pub asks: BTreeMap<Price, Orders>,
-    pub orders: HashMap<OrderId, Order>,
-    data: HashMap<Price, LevelData>,
+/// A client order id together with the participant that scopes it
+type ClientOrderKey = (Option<ParticipantId>, ClientOrderId);
+
+/// Filled orders remembered by default
+const DEFAULT_FILLED_ORDER_HISTORY: usize = 10_000;
+
+/// Limit order book over price type `P` and quantity type `Q`, which
+/// default to `Price` and `Quantity`
+#[derive(Debug)]
+pub struct OrderBook<P = Price, Q = Quantity> {
+    bids: Box<dyn PriceLadder<P>>,
+    asks: Box<dyn PriceLadder<P>>,
+    /// Slab handle of every resting order
+    orders: HashMap<OrderId, OrderHandle>,
+    /// The resting orders themselves, queued by price level
+    slab: OrderSlab<P, Q>,
+    pub stop_book: StopBook<P, Q>,
+    bid_depth: Depth<P, Q>,
+    ask_depth: Depth<P, Q>,
     next_order_id: OrderId,
+    last_trade_price: Option<P>,
+    /// Lowest and highest prices traded since stops were last released
+    traded_range: Option<(P, P)>,
+    clock: Box<dyn Clock>,
+    market_schedule: Option<MarketSchedule>,
+    next_market_close: Option<Timestamp>,
+    expiries: BTreeMap<Timestamp, OrderIds>,
+    pegged: BTreeSet<OrderId>,
+    matching_policy: Box<dyn MatchingPolicy<P, Q>>,
+    auction: bool,
+    trading_phase: TradingPhase,
+    events: Vec<BookEvent<P, Q>>,
+    price_band: Option<PriceBand<P>>,
+    instrument_rules: InstrumentRules<P, Q>,
+    client_orders: HashMap<ClientOrderKey, OrderId>,
+    recent_client_orders: HashSet<ClientOrderKey>,
+    client_order_history: VecDeque<ClientOrderKey>,
+    client_order_history_size: usize,
+    participant_orders: HashMap<ParticipantId, BTreeSet<OrderId>>,
+    filled_orders: HashSet<OrderId>,
+    filled_order_history: VecDeque<OrderId>,
+    filled_order_history_size: usize,
+}
+
+impl<P: PriceValue, Q: QuantityValue> Default for OrderBook<P, Q> {
+    fn default() -> Self {
+        Self {
+            bids: Box::new(TreeLadder::new()),
+            asks: Box::new(TreeLadder::new()),
+            orders: HashMap::new(),
+            slab: OrderSlab::new(),
+            stop_book: StopBook::default(),
+            bid_depth: Depth::new(Side::Buy),
+            ask_depth: Depth::new(Side::Sell),
+            next_order_id: 0,
+            last_trade_price: None,
+            traded_range: None,
+            clock: Box::default(),
+            market_schedule: None,
+            next_market_close: None,
+            expiries: BTreeMap::new(),
+            pegged: BTreeSet::new(),
+            matching_policy: Box::default(),
+            auction: false,
+            trading_phase: TradingPhase::default(),
+            events: Vec::new(),
+            price_band: None,
+            instrument_rules: InstrumentRules::default(),
+            client_orders: HashMap::new(),
+            recent_client_orders: HashSet::new(),
+            client_order_history: VecDeque::new(),
+            client_order_history_size: 0,
+            participant_orders: HashMap::new(),
+            filled_orders: HashSet::new(),
+            filled_order_history: VecDeque::new(),
+            filled_order_history_size: DEFAULT_FILLED_ORDER_HISTORY,
+        }
+    }
 }
 
 impl OrderBook {
+    /// Create an order book over the default `Price` and `Quantity` types;
+    /// other types start from `OrderBook::<P, Q>::default()`
     pub fn new() -> Self {
         Self::default()
     }
 
-    fn update_level_data(&mut self, price: Price, quantity: Quantity, action: LevelAction) {
-        let data: &mut LevelData = self.data.entry(price).or_insert_with(LevelData::new);
+    /// Create an order book that reads time from `clock`
+    pub fn with_clock(clock: Box<dyn Clock>) -> Self {
+        Self {
+            clock,
+            ..Self::default()
+        }
+    }
+}
+
+impl<P: PriceValue, Q: QuantityValue> OrderBook<P, Q> {
+    /// Read time from `clock` from now on
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+    }
 
-        match action {
-            LevelAction::Add => {
-                data.count += 1;
-                data.quantity += quantity;
-            }
-            LevelAction::Remove => {
-                data.count = data.count.saturating_sub(1);
-                data.quantity = data.quantity.saturating_sub(quantity);
-            }
-            LevelAction::Match => {
-                data.quantity = data.quantity.saturating_sub(quantity);
-            }
+    /// Set the daily market close used to expire Good For Day orders
+    pub fn set_market_schedule(&mut self, schedule: MarketSchedule) {
+        self.market_schedule = Some(schedule);
+        self.next_market_close = Some(schedule.next_close_after(self.clock.now()));
+    }
+
+    pub fn get_market_schedule(&self) -> Option<MarketSchedule> {
+        self.market_schedule
+    }
+
+    /// Next market close the book will act on, if a schedule is set
+    pub fn get_next_market_close(&self) -> Option<Timestamp> {
+        self.next_market_close
+    }
+
+    /// Keep each side's price levels in a copy of `ladder`, moving any
+    /// levels already in the book across
+    pub fn set_price_ladder<L: PriceLadder<P> + Clone + 'static>(&mut self, ladder: L) {
+        let mut bids: Box<dyn PriceLadder<P>> = Box::new(ladder.clone());
+        let mut asks: Box<dyn PriceLadder<P>> = Box::new(ladder);
+        for (price, level) in self.bids.iter() {
+            *bids.entry(price) = *level;
+        }
+        for (price, level) in self.asks.iter() {
+            *asks.entry(price) = *level;
+        }
+        self.bids = bids;
+        self.asks = asks;
+    }
+
+    /// Set how aggressor quantity is shared across resting orders at a level
+    pub fn set_matching_policy(&mut self, policy: Box<dyn MatchingPolicy<P, Q>>) {
+        self.matching_policy = policy;
+    }
+
+    /// Current time according to the book's clock
+    pub fn now(&self) -> Timestamp {
+        self.clock.now()
+    }
+
+    fn depth_mut(&mut self, side: Side) -> &mut Depth<P, Q> {
+        match side {
+            Side::Buy => &mut self.bid_depth,
+            Side::Sell => &mut self.ask_depth,
         }
+    }
 
-        if data.count == 0 {
-            self.data.remove(&price);
+    fn update_level_data(&mut self, side: Side, price: P, quantity: Q, action: LevelAction) {
+        let depth: &mut Depth<P, Q> = self.depth_mut(side);
+        match action {
+            // Callers check `has_level_room` before resting an order
+            LevelAction::Add => depth.add_order(price, quantity),
+            LevelAction::Remove => depth.remove_order(price, quantity),
+            LevelAction::Match => depth.reduce(price, quantity),
         }
     }
 
-    fn on_order_added(&mut self, order: &Order) {
-        self.update_level_data(order.get_price(), order.get_initial_quantity(), LevelAction::Add);
+    fn on_order_added(&mut self, order: &Order<P, Q>) {
+        self.update_level_data(order.get_side(), order.get_price(), order.get_remaining_quantity(), LevelAction::Add);
     }
 
-    fn on_order_cancelled(&mut self, order: &Order) {
-        self.update_level_data(order.get_price(), order.get_remaining_quantity(), LevelAction::Remove);
+    fn on_order_cancelled(&mut self, order: &Order<P, Q>) {
+        self.update_level_data(order.get_side(), order.get_price(), order.get_remaining_quantity(), LevelAction::Remove);
     }
 
-    fn on_order_matched(&mut self, price: Price, quantity: Quantity, is_fully_filled: bool) {
+    fn on_order_matched(&mut self, side: Side, price: P, quantity: Q, is_fully_filled: bool) {
         let action: LevelAction = if is_fully_filled {
             LevelAction::Remove
         } else {
             LevelAction::Match
         };
-        self.update_level_data(price, quantity, action);
+        self.update_level_data(side, price, quantity, action);
+    }
+
+    /// Whether `quantity` more can rest on `side` at `price` without the
+    /// level total overflowing
+    fn has_level_room(&self, side: Side, price: P, quantity: Q) -> bool {
+        self.get_depth(side).has_room(price, quantity)
     }
 
-    fn can_match(&self, side: Side, price: Price) -> bool {
+    fn can_match(&self, side: Side, price: P) -> bool {
         match side {
             Side::Buy => {
-                if self.asks.is_empty() {
+                let Some(best_ask) = self.get_best_ask() else {
                     return false;
-                }
-                let best_ask: Price = *self.asks.keys().next().unwrap();
+                };
                 price >= best_ask
             }
             Side::Sell => {
-                if self.bids.is_empty() {
+                let Some(best_bid) = self.get_best_bid() else {
                     return false;
-                }
-                let best_bid: Price = *self.bids.keys().next_back().unwrap();
+                };
                 price <= best_bid
             }
         }
     }
 
-    fn can_fully_fill(&self, side: Side, price: Price, mut quantity: Quantity) -> bool {
-        if !self.can_match(side, price) {
-            return false;
-        }
+    /// Whether an order on `side` limited at `price` can trade all of
+    /// `quantity` against the opposite side
+    fn can_fully_fill(&self, side: Side, price: P, quantity: Q) -> bool {
+        self.get_depth(side.opposite()).can_fill(price, quantity)
+    }
 
-        let threshold: Option<Price> = match side {
-            Side::Buy => {
-                if let Some(ask_price) = self.asks.keys().next() {
-                    Some(*ask_price)
-                } else {
-                    return false;
-                }
+    /// Match crossing orders. Trades execute at the resting order's price, so
+    /// the aggressor's side decides the last trade price. The matching policy
+    /// decides how the aggressor's quantity is shared across a resting level.
+    fn match_orders(&mut self, aggressor: Side) -> Trades<P, Q> {
+        let mut trades: Trades<P, Q> = Vec::new();
+
+        // The band stays centred where it was when matching started, so a
+        // sweep cannot drag its own reference along with it
+        let price_band: Option<(PriceBand<P>, P)> = self.active_price_band();
+
+        loop {
+            // A breach earlier in this loop may have paused trading
+            if self.auction || self.trading_phase != TradingPhase::Open {
+                break;
             }
-            Side::Sell => {
-                if let Some(bid_price) = self.bids.keys().next_back() {
-                    Some(*bid_price)
-                } else {
-                    return false;
+
+            let (Some(bid_price), Some(ask_price)) = (self.get_best_bid(), self.get_best_ask()) else {
+                break;
+            };
+
+            if bid_price < ask_price {
+                break;
+            }
+
+            let (incoming_price, resting_price) = match aggressor {
+                Side::Buy => (bid_price, ask_price),
+                Side::Sell => (ask_price, bid_price),
+            };
+            if let Some((band, reference_price)) = price_band
+                && !band.contains(reference_price, resting_price)
+            {
+                let Some(order_id) = self.get_level_orders(aggressor, incoming_price).next().map(|order: &Order<P, Q>| order.get_order_id()) else {
+                    break;
+                };
+                self.pause_trading(order_id, resting_price, reference_price, band.action);
+                // Resting what is left of the breaching order would keep the
+                // book crossed outside the band
+                if let Some(order) = self.remove_order(order_id) {
+                    self.publish_cancel(&order, CancelReason::PriceBandBreach);
                 }
+                break;
             }
-        };
 
-        for (level_price, level_data) in &self.data {
-            if let Some(threshold_price) = threshold {
-                let skip_level: bool = match side {
-                    Side::Buy => threshold_price > *level_price,
-                    Side::Sell => threshold_price < *level_price,
+            let mut matched_trades: MatchedTrades<P, Q> = Vec::new();
+            let mut filled: Orders<P, Q> = Vec::new();
+
+            // Process matching within this price level
+            {
+                let (incoming_level, resting_level): (&mut PriceLevel, &mut PriceLevel) = match aggressor {
+                    Side::Buy => (self.bids.entry(bid_price), self.asks.entry(ask_price)),
+                    Side::Sell => (self.asks.entry(ask_price), self.bids.entry(bid_price)),
                 };
-                if skip_level {
-                    continue;
+                let slab: &mut OrderSlab<P, Q> = &mut self.slab;
+
+                let Some(incoming_handle) = incoming_level.front() else {
+                    break;
+                };
+                let incoming_quantity: Q = slab.get(incoming_handle).map_or(Q::ZERO, |order: &Order<P, Q>| order.get_remaining_quantity());
+                let allocations: Vec<Q> = self.matching_policy.allocate(incoming_quantity, &mut slab.iter(resting_level));
+
+                let mut replenished: Vec<OrderHandle> = Vec::new();
+                let mut next: Option<OrderHandle> = resting_level.front();
+
+                for allocation in allocations {
+                    let Some(resting_handle) = next else {
+                        break;
+                    };
+                    next = slab.next(resting_handle);
+
+                    let (Some(incoming), Some(resting)) = (slab.get(incoming_handle), slab.get(resting_handle)) else {
+                        break;
+                    };
+                    // Resting icebergs only trade their displayed slice
+                    let quantity: Q = allocation
+                        .min(resting.get_visible_quantity())
+                        .min(incoming.get_remaining_quantity());
+                    if quantity == Q::ZERO {
+                        continue;
+                    }
+
+                    if let Some(incoming) = slab.get_mut(incoming_handle) {
+                        incoming.fill(quantity);
+                        // The aggressor can trade its hidden iceberg quantity
+                        incoming.replenish();
+                    }
+                    if let Some(resting) = slab.get_mut(resting_handle) {
+                        resting.fill(quantity);
+                    }
+
+                    let (Some(incoming), Some(resting)) = (slab.get(incoming_handle), slab.get(resting_handle)) else {
+                        break;
+                    };
+                    let (bid, ask): (&Order<P, Q>, &Order<P, Q>) = match aggressor {
+                        Side::Buy => (incoming, resting),
+                        Side::Sell => (resting, incoming),
+                    };
+                    matched_trades.push((
+                        TradeInfo::new(bid.get_order_id(), bid.get_price(), quantity).with_participant(bid.get_participant()),
+                        TradeInfo::new(ask.get_order_id(), ask.get_price(), quantity).with_participant(ask.get_participant()),
+                        bid.is_filled(),
+                        ask.is_filled(),
+                    ));
+
+                    if resting.is_filled() {
+                        filled.extend(slab.remove(resting_level, resting_handle));
+                    } else if resting.needs_replenish() {
+                        replenished.push(resting_handle);
+                    }
+                }
+
+                // A new iceberg slice goes to the back of the queue
+                for handle in replenished {
+                    if let Some(resting) = slab.get_mut(handle) {
+                        resting.replenish();
+                    }
+                    slab.move_to_back(resting_level, handle);
+                }
+
+                if slab.get(incoming_handle).is_some_and(|incoming: &Order<P, Q>| incoming.is_filled()) {
+                    filled.extend(slab.remove(incoming_level, incoming_handle));
                 }
             }
 
-            let price_check: bool = match side {
-                Side::Buy => *level_price > price,
-                Side::Sell => *level_price < price,
-            };
-            if price_check {
-                continue;
+            // A policy that allocates nothing would otherwise spin forever
+            if matched_trades.is_empty() {
+                break;
             }
 
-            if quantity <= level_data.quantity {
-                return true;
+            for order in filled {
+                self.orders.remove(&order.get_order_id());
+                self.forget_order(&order);
+                self.untrack_live_order(&order);
+                self.remember_filled_order(order.get_order_id());
             }
 
-            quantity = quantity.saturating_sub(level_data.quantity);
-        }
+            for (bid_info, ask_info, bid_filled, ask_filled) in matched_trades {
+                self.record_trade_price(match aggressor {
+                    Side::Buy => ask_info.price,
+                    Side::Sell => bid_info.price,
+                });
 
-        false
-    }
+                self.on_order_matched(Side::Buy, bid_info.price, bid_info.quantity, bid_filled);
+                self.on_order_matched(Side::Sell, ask_info.price, ask_info.quantity, ask_filled);
+                trades.push(Trade::new(bid_info, ask_info));
+            }
 
-    fn match_orders(&mut self) -> Trades {
-        let mut trades: Trades = Vec::new();
+            if self.bids.get(bid_price).is_none_or(|level: &PriceLevel| level.is_empty()) {
+                self.bids.remove(bid_price);
+            }
 
-        loop {
-            if self.bids.is_empty() || self.asks.is_empty() {
-                break;
+            if self.asks.get(ask_price).is_none_or(|level: &PriceLevel| level.is_empty()) {
+                self.asks.remove(ask_price);
             }
+        }
 
-            let (bid_price, ask_price) = {
-                let bid_price = *self.bids.keys().next_back().unwrap();
-                let ask_price = *self.asks.keys().next().unwrap();
-                (bid_price, ask_price)
-            };
+        trades
+    }
 
-            if bid_price < ask_price {
-                break;
-            }
+    pub fn add_order(&mut self, side: Side, ord_type: OrdType, time_in_force: TimeInForce, price: P, quantity: Q) -> (OrderId, Trades<P, Q>) {
+        let result: OrderResult<P, Q> = self.add_order_with_status(side, ord_type, time_in_force, price, quantity);
+        (result.order_id, result.trades)
+    }
 
-            let mut matched_trades: MatchedTrades = Vec::new();
-            let mut bid_orders_to_remove: OrderIds = Vec::new();
-            let mut ask_orders_to_remove: OrderIds = Vec::new();
-            let mut bid_orders_to_update: Orders = Vec::new();
-            let mut ask_orders_to_update: Orders = Vec::new();
+    pub fn add_order_with_status(&mut self, side: Side, ord_type: OrdType, time_in_force: TimeInForce, price: P, quantity: Q) -> OrderResult<P, Q> {
+        self.submit_order(OrderRequest::new(side, ord_type, time_in_force, price, quantity))
+    }
 
-            // Process matching within this price level
-            {
-                let bid_orders: &mut Orders = self.bids.get_mut(&bid_price).unwrap();
-                let ask_orders: &mut Orders = self.asks.get_mut(&ask_price).unwrap();
+    /// Submit an order request, assigning it the next order id.
+    ///
+    /// Stop and StopLimit orders wait in the stop book until the last trade
+    /// price reaches their stop price. Any stops triggered by trades from this
+    /// order are released into the book, and their trades are included in the
+    /// result.
+    pub fn submit_order(&mut self, request: OrderRequest<P, Q>) -> OrderResult<P, Q> {
+        let order_id: OrderId = self.next_order_id;
+        self.next_order_id += 1;
 
-                while !bid_orders.is_empty() && !ask_orders.is_empty() {
-                    let mut bid: Order = bid_orders[0].clone();
-                    let mut ask: Order = ask_orders[0].clone();
+        if let Some(status) = self.phase_rejection() {
+            return OrderResult::new(order_id, status, Vec::new());
+        }
 
-                    let quantity: Quantity = std::cmp::min(bid.get_remaining_quantity(), ask.get_remaining_quantity());
+        let order: Order<P, Q> = request.to_order(order_id);
 
-                    bid.fill(quantity);
-                    ask.fill(quantity);
+        if Self::client_order_key(&order).is_some_and(|key: ClientOrderKey| self.is_client_order_in_use(&key)) {
+            return OrderResult::new(order_id, OrderStatus::RejectedDuplicateId, Vec::new());
+        }
 
-                    let bid_filled: bool = bid.is_filled();
-                    let ask_filled: bool = ask.is_filled();
+        if !Self::is_valid_combination(&order) {
+            return OrderResult::new(order_id, OrderStatus::RejectedInvalidCombination, Vec::new());
+        }
 
-                    matched_trades.push((bid.get_order_id(), bid.get_price(), ask.get_order_id(), ask.get_price(), quantity, bid_filled, ask_filled));
+        if order.get_display_quantity() == Some(Q::ZERO) {
+            return OrderResult::new(order_id, OrderStatus::RejectedInvalidDisplayQuantity, Vec::new());
+        }
 
-                    if bid_filled {
-                        bid_orders_to_remove.push(bid.get_order_id());
-                        bid_orders.remove(0);
-                    } else {
-                        bid_orders[0] = bid.clone();
-                        bid_orders_to_update.push(bid);
-                    }
+        if let Some(status) = self.instrument_rules.validate(&order) {
+            return OrderResult::new(order_id, status, Vec::new());
+        }
 
-                    if ask_filled {
-                        ask_orders_to_remove.push(ask.get_order_id());
-                        ask_orders.remove(0);
-                    } else {
-                        ask_orders[0] = ask.clone();
-                        ask_orders_to_update.push(ask);
-                    }
+        if order.get_time_in_force() == TimeInForce::GoodTillDate {
+            match order.get_expiry() {
+                None => return OrderResult::new(order_id, OrderStatus::RejectedMissingExpiry, Vec::new()),
+                Some(expiry) if expiry <= self.clock.now() => {
+                    return OrderResult::new(order_id, OrderStatus::RejectedExpired, Vec::new());
                 }
+                Some(_) => {}
             }
+        }
 
-            for order_id in bid_orders_to_remove {
-                self.orders.remove(&order_id);
-            }
-            for order_id in ask_orders_to_remove {
-                self.orders.remove(&order_id);
+        let mut result: OrderResult<P, Q> = if order.is_stop() {
+            match self.add_stop_order(order) {
+                Some(result) => result,
+                None => return OrderResult::new(order_id, OrderStatus::PendingTrigger, Vec::new()),
             }
-            for order in bid_orders_to_update {
-                self.orders.insert(order.get_order_id(), order);
+        } else {
+            self.place_order(order)
+        };
+
+        let triggered_trades: Trades<P, Q> = self.release_triggered_stops();
+        if !triggered_trades.is_empty() {
+            result.trades.extend(triggered_trades);
+            result.status = OrderStatus::Executed;
+        }
+
+        self.reprice_pegged_orders();
+        result
+    }
+
+    /// Whether the order's instructions make sense for its order type and
+    /// time in force. Post-only and pegged orders must be able to rest, and
+    /// only stop orders can trail.
+    fn is_valid_combination(order: &Order<P, Q>) -> bool {
+        let ord_type: OrdType = order.get_ord_type();
+        let can_rest: bool = order.get_time_in_force().can_rest();
+
+        if order.get_post_only().is_some() && !(can_rest && matches!(ord_type, OrdType::Limit | OrdType::StopLimit)) {
+            return false;
+        }
+        if order.get_peg().is_some() && !(can_rest && ord_type == OrdType::Limit) {
+            return false;
+        }
+        if order.get_trail().is_some() && !order.is_stop() {
+            return false;
+        }
+        true
+    }
+
+    /// Rest a stop order in the stop book, or place it straight away if the
+    /// last trade price has already reached its stop price
+    fn add_stop_order(&mut self, mut order: Order<P, Q>) -> Option<OrderResult<P, Q>> {
+        if let Some(trail) = order.get_trail() {
+            if !trail.is_positive() {
+                return Some(OrderResult::new(order.get_order_id(), OrderStatus::RejectedInvalidTrailAmount, Vec::new()));
             }
-            for order in ask_orders_to_update {
-                self.orders.insert(order.get_order_id(), order);
+            // Trailing stops start from the last trade when it beats the given stop price
+            if let Some(stop_price) = self.last_trade_price
+                .and_then(|last: P| order.trailing_stop_price(last, self.instrument_rules.tick_table.tick_size_at(last)))
+            {
+                order.stop_price = Some(stop_price);
             }
+        }
 
-            for (bid_id, bid_price, ask_id, ask_price, quantity, bid_filled, ask_filled) in matched_trades {
-                trades.push(Trade::new(
-                    TradeInfo::new(bid_id, bid_price, quantity),
-                    TradeInfo::new(ask_id, ask_price, quantity),
-                ));
+        let Some(stop_price) = order.get_stop_price() else {
+            return Some(OrderResult::new(order.get_order_id(), OrderStatus::RejectedMissingStopPrice, Vec::new()));
+        };
 
-                self.on_order_matched(bid_price, quantity, bid_filled);
-                self.on_order_matched(ask_price, quantity, ask_filled);
-            }
+        let already_triggered: bool = self.last_trade_price
+            .is_some_and(|last: P| StopBook::<P, Q>::is_triggered(order.get_side(), stop_price, last));
+        if already_triggered {
+            order.trigger();
+            return Some(self.place_order(order));
+        }
+
+        self.add_expiry(&order);
+        self.track_live_order(&order);
+        self.stop_book.insert(order);
+        None
+    }
+
+    /// Make `price` the last trade price and widen the range traded since
+    /// stops were last released
+    fn record_trade_price(&mut self, price: P) {
+        self.last_trade_price = Some(price);
+        self.traded_range = Some(match self.traded_range {
+            Some((low, high)) => (low.min(price), high.max(price)),
+            None => (price, price),
+        });
+    }
 
-            if self.bids.get(&bid_price).map_or(true, |orders: &Orders| orders.is_empty()) {
-                self.bids.remove(&bid_price);
-                self.data.remove(&bid_price);
+    /// Release stops triggered by any price traded since the last release,
+    /// then ratchet trailing stops to the last trade price and release those
+    /// it triggers, until no more trigger
+    fn release_triggered_stops(&mut self) -> Trades<P, Q> {
+        let mut trades: Trades<P, Q> = Vec::new();
+
+        while let Some(last_trade_price) = self.last_trade_price {
+            let (low, high) = self.traded_range.take().unwrap_or((last_trade_price, last_trade_price));
+            let mut triggered: Orders<P, Q> = self.stop_book.take_triggered_in_range(low, high);
+            let tick_size: P = self.instrument_rules.tick_table.tick_size_at(last_trade_price);
+            self.stop_book.ratchet_trailing_stops(last_trade_price, tick_size);
+            triggered.extend(self.stop_book.take_triggered(last_trade_price));
+            if triggered.is_empty() {
+                break;
             }
 
-            if self.asks.get(&ask_price).map_or(true, |orders:&Orders| orders.is_empty()) {
-                self.asks.remove(&ask_price);
-                self.data.remove(&ask_price);
+            for mut order in triggered {
+                self.remove_expiry(&order);
+                order.trigger();
+                let order_id: OrderId = order.get_order_id();
+                let participant: Option<ParticipantId> = order.get_participant();
+                let client_order_key: Option<ClientOrderKey> = Self::client_order_key(&order);
+                let released: Order<P, Q> = order.clone();
+                let result: OrderResult<P, Q> = self.place_order(order);
+                if result.status.is_rejected() {
+                    self.publish_cancel(&released, CancelReason::StopRejected(result.status));
+                }
+                trades.extend(result.trades);
+                if !self.is_live(order_id) {
+                    self.untrack_order_id(order_id, participant, client_order_key);
+                }
             }
         }
 
-        let mut orders_to_cancel: OrderIds = Vec::new();
+        trades
+    }
 
-        if !self.bids.is_empty() {
-            let best_bid_price: Price = *self.bids.keys().next_back().unwrap();
-            if let Some(bid_orders) = self.bids.get(&best_bid_price) {
-                if let Some(order) = bid_orders.first() {
-                    if order.get_order_type() == OrderType::FillAndKill {
-                        orders_to_cancel.push(order.get_order_id());
-                    }
-                }
+    fn place_order(&mut self, mut order: Order<P, Q>) -> OrderResult<P, Q> {
+        let order_id: OrderId = order.get_order_id();
+        let side: Side = order.get_side();
+        let time_in_force: TimeInForce = order.get_time_in_force();
+
+        if self.auction {
+            if !time_in_force.can_rest() {
+                return OrderResult::new(order_id, OrderStatus::RejectedImmediateOrderInAuction, Vec::new());
+            }
+            // Market orders wait at the most aggressive price until the uncross
+            if auction::is_auction_market_order(&order) {
+                order.price = match side {
+                    Side::Buy => P::MAX,
+                    Side::Sell => P::MIN,
+                };
+            }
+        } else {
+            // Market orders may sweep to the worst opposite level, market-to-limit
+            // orders only take the best one
+            let market_price: Option<Option<P>> = match (order.get_ord_type(), side) {
+                (OrdType::Market, Side::Buy) => Some(self.asks.max_price()),
+                (OrdType::Market, Side::Sell) => Some(self.bids.min_price()),
+                (OrdType::MarketToLimit, Side::Buy) => Some(self.get_best_ask()),
+                (OrdType::MarketToLimit, Side::Sell) => Some(self.get_best_bid()),
+                _ => None,
+            };
+            match market_price {
+                Some(Some(price)) => order.to_limit(price),
+                Some(None) => return OrderResult::new(order_id, OrderStatus::RejectedNoLiquidity, Vec::new()),
+                None => {}
             }
         }
 
-        if !self.asks.is_empty() {
-            let best_ask_price: Price = *self.asks.keys().next().unwrap();
-            if let Some(ask_orders) = self.asks.get(&best_ask_price) {
-                if let Some(order) = ask_orders.first() {
-                    if order.get_order_type() == OrderType::FillAndKill {
-                        orders_to_cancel.push(order.get_order_id());
-                    }
+        if order.get_peg().is_some() {
+            match self.pegged_price(&order) {
+                Some(price) => order.reprice(price),
+                None => return OrderResult::new(order_id, OrderStatus::RejectedNoPegReference, Vec::new()),
+            }
+        }
+
+        let mut repriced: bool = false;
+        // Nothing trades on entry during an auction, so post-only orders
+        // cannot take liquidity there
+        if let Some(mode) = order.get_post_only()
+            && !self.auction
+            && self.can_match(side, order.get_price())
+        {
+            match (mode, self.post_only_slide_price(side)) {
+                (PostOnly::Slide, Some(price)) => {
+                    order.reprice(price);
+                    repriced = true;
                 }
+                _ => return OrderResult::new(order_id, OrderStatus::RejectedPostOnlyWouldCross, Vec::new()),
             }
         }
 
-        for order_id in orders_to_cancel {
-            self.cancel_order_internal(order_id);
+        if time_in_force == TimeInForce::FillAndKill && !self.can_match(side, order.get_price()) {
+            return OrderResult::new(order_id, OrderStatus::RejectedFillAndKillNoMatch, Vec::new());
         }
 
-        trades
-    }
+        if time_in_force == TimeInForce::FillOrKill && !self.can_fully_fill(side, self.band_capped_price(side, order.get_price()), order.get_initial_quantity()) {
+            return OrderResult::new(order_id, OrderStatus::RejectedFillOrKillPartialFill, Vec::new());
+        }
 
-    pub fn add_order(&mut self, side: Side, order_type: OrderType, price: Price, quantity: Quantity) -> (OrderId, Trades) {
-        let order_id: OrderId = self.next_order_id;
-        self.next_order_id += 1;
+        if !self.has_level_room(side, order.get_price(), order.get_remaining_quantity()) {
+            return OrderResult::new(order_id, OrderStatus::RejectedQuantityOverflow, Vec::new());
+        }
 
-        let mut order: Order = Order::new(order_id, side, order_type, price, quantity);
+        self.rest_order(order);
 
-        if self.orders.contains_key(&order_id) {
-            return (order_id, Vec::new());
+        if self.auction {
+            return OrderResult::new(order_id, OrderStatus::Accepted, Vec::new());
         }
 
-        if order_type == OrderType::Market {
-            match side {
-                Side::Buy => {
-                    if !self.asks.is_empty() {
-                        let worst_ask: Price = *self.asks.keys().next_back().unwrap();
-                        order.to_good_till_cancel(worst_ask);
-                    } else {
-                        return (order_id, Vec::new());
-                    }
-                }
-                Side::Sell => {
-                    if !self.bids.is_empty() {
-                        let worst_bid: Price = *self.bids.keys().next().unwrap();
-                        order.to_good_till_cancel(worst_bid);
-                    } else {
-                        return (order_id, Vec::new());
-                    }
-                }
-            }
-        }
+        let trades: Trades<P, Q> = self.match_orders(side);
 
-        if order_type == OrderType::FillAndKill && !self.can_match(side, order.get_price()) {
-            return (order_id, Vec::new());
+        // Immediate orders never rest: cancel whatever did not execute
+        if !time_in_force.can_rest() {
+            self.remove_order(order_id);
         }
 
-        if order_type == OrderType::FillOrKill && !self.can_fully_fill(side, order.get_price(), order.get_initial_quantity()) {
-            return (order_id, Vec::new());
+        let status: OrderStatus = if !trades.is_empty() {
+            OrderStatus::Executed
+        } else if repriced {
+            OrderStatus::Repriced
+        } else {
+            OrderStatus::Accepted
+        };
+
+        OrderResult::new(order_id, status, trades)
+    }
+
+    /// Add an order to the back of its price level and index it
+    fn rest_order(&mut self, order: Order<P, Q>) {
+        let after: Option<OrderHandle> = self.levels(order.get_side())
+            .get(order.get_price())
+            .and_then(|level: &PriceLevel| level.back());
+        self.rest_order_after(order, after);
+    }
+
+    /// Add an order to its price level right after `after`, or at the front
+    /// when `after` is None, and index it
+    fn rest_order_after(&mut self, order: Order<P, Q>, after: Option<OrderHandle>) {
+        self.on_order_added(&order);
+        self.add_expiry(&order);
+        self.track_live_order(&order);
+        if order.get_peg().is_some() {
+            self.pegged.insert(order.get_order_id());
         }
 
+        let order_id: OrderId = order.get_order_id();
+        let levels: &mut dyn PriceLadder<P> = match order.get_side() {
+            Side::Buy => self.bids.as_mut(),
+            Side::Sell => self.asks.as_mut(),
+        };
+        let level: &mut PriceLevel = levels.entry(order.get_price());
+        let handle: OrderHandle = self.slab.insert_after(level, after, order);
+        self.orders.insert(order_id, handle);
+    }
+
+    fn levels(&self, side: Side) -> &dyn PriceLadder<P> {
         match side {
-            Side::Buy => {
-                self.bids.entry(order.get_price()).or_insert_with(Vec::new).push(order.clone());
-            }
-            Side::Sell => {
-                self.asks.entry(order.get_price()).or_insert_with(Vec::new).push(order.clone());
-            }
+            Side::Buy => self.bids.as_ref(),
+            Side::Sell => self.asks.as_ref(),
         }
+    }
 
-        self.orders.insert(order_id, order.clone());
-        self.on_order_added(&order);
+    /// Orders resting at `price` on `side`, in time priority
+    pub fn get_level_orders(&self, side: Side, price: P) -> impl Iterator<Item = &Order<P, Q>> {
+        self.levels(side)
+            .get(price)
+            .into_iter()
+            .flat_map(|level: &PriceLevel| self.slab.iter(level))
+    }
 
-        let trades: Trades = self.match_orders();
-        (order_id, trades)
+    /// Every resting order, in no particular order
+    fn resting_orders(&self) -> impl Iterator<Item = &Order<P, Q>> {
+        self.orders.values().filter_map(|handle: &OrderHandle| self.slab.get(*handle))
     }
 
-    pub fn add_order_with_status(&mut self, side: Side, order_type: OrderType, price: Price, quantity: Quantity) -> OrderResult {
-        let order_id = self.next_order_id;
-        self.next_order_id += 1;
+    /// Drop a resting order from the expiry and peg indexes once it leaves the book
+    fn forget_order(&mut self, order: &Order<P, Q>) {
+        self.remove_expiry(order);
+        self.pegged.remove(&order.get_order_id());
+    }
 
-        let mut order: Order = Order::new(order_id, side, order_type, price, quantity);
+    /// Whether the order is resting in the book or waiting in the stop book
+    fn is_live(&self, order_id: OrderId) -> bool {
+        self.orders.contains_key(&order_id) || self.stop_book.contains(order_id)
+    }
 
-        if self.orders.contains_key(&order_id) {
-            return OrderResult::new(order_id, OrderStatus::RejectedDuplicateId, Vec::new());
+    fn client_order_key(order: &Order<P, Q>) -> Option<ClientOrderKey> {
+        order.get_client_order_id().map(|client_order_id: ClientOrderId| (order.get_participant(), client_order_id))
+    }
+
+    /// Whether a live order, or a recent one within the history window,
+    /// already uses the client order id
+    fn is_client_order_in_use(&self, key: &ClientOrderKey) -> bool {
+        self.client_orders.contains_key(key) || self.recent_client_orders.contains(key)
+    }
+
+    /// Index the order under its participant, and mark its client order id
+    /// as in use, while the order is live
+    fn track_live_order(&mut self, order: &Order<P, Q>) {
+        if let Some(participant) = order.get_participant() {
+            self.participant_orders.entry(participant).or_default().insert(order.get_order_id());
         }
+        if let Some(key) = Self::client_order_key(order) {
+            self.client_orders.insert(key, order.get_order_id());
+        }
+    }
 
-        if order_type == OrderType::Market {
-            match side {
-                Side::Buy => {
-                    if !self.asks.is_empty() {
-                        let worst_ask: Price = *self.asks.keys().next_back().unwrap();
-                        order.to_good_till_cancel(worst_ask);
-                    } else {
-                        return OrderResult::new(order_id, OrderStatus::RejectedNoLiquidity, Vec::new());
-                    }
-                }
-                Side::Sell => {
-                    if !self.bids.is_empty() {
-                        let worst_bid: Price = *self.bids.keys().next().unwrap();
-                        order.to_good_till_cancel(worst_bid);
-                    } else {
-                        return OrderResult::new(order_id, OrderStatus::RejectedNoLiquidity, Vec::new());
-                    }
-                }
+    /// Drop the order from its participant's index and free its client order
+    /// id once the order has left the book, remembering the id in the history
+    /// window
+    fn untrack_live_order(&mut self, order: &Order<P, Q>) {
+        self.untrack_order_id(order.get_order_id(), order.get_participant(), Self::client_order_key(order));
+    }
+
+    /// `untrack_live_order` for an order that has already been handed on
+    fn untrack_order_id(&mut self, order_id: OrderId, participant: Option<ParticipantId>, client_order_key: Option<ClientOrderKey>) {
+        if let Some(participant) = participant
+            && let Some(order_ids) = self.participant_orders.get_mut(&participant)
+        {
+            order_ids.remove(&order_id);
+            if order_ids.is_empty() {
+                self.participant_orders.remove(&participant);
             }
         }
 
-        if order_type == OrderType::FillAndKill && !self.can_match(side, order.get_price()) {
-            return OrderResult::new(order_id, OrderStatus::RejectedFillAndKillNoMatch, Vec::new());
+        let Some(key) = client_order_key else {
+            return;
+        };
+        if self.client_orders.get(&key) != Some(&order_id) {
+            return;
         }
+        self.client_orders.remove(&key);
 
-        if order_type == OrderType::FillOrKill && !self.can_fully_fill(side, order.get_price(), order.get_initial_quantity()) {
-            return OrderResult::new(order_id, OrderStatus::RejectedFillOrKillPartialFill, Vec::new());
+        if self.client_order_history_size > 0 {
+            self.recent_client_orders.insert(key);
+            self.client_order_history.push_back(key);
+            self.trim_client_order_history();
         }
+    }
+
+    fn trim_client_order_history(&mut self) {
+        while self.client_order_history.len() > self.client_order_history_size {
+            if let Some(key) = self.client_order_history.pop_front() {
+                self.recent_client_orders.remove(&key);
+            }
+        }
+    }
+
+    /// Also reject client order ids used by the last `size` orders that have
+    /// left the book. Zero, the default, only checks live orders.
+    pub fn set_client_order_history(&mut self, size: usize) {
+        self.client_order_history_size = size;
+        self.trim_client_order_history();
+    }
+
+    fn remember_filled_order(&mut self, order_id: OrderId) {
+        if self.filled_order_history_size > 0 && self.filled_orders.insert(order_id) {
+            self.filled_order_history.push_back(order_id);
+            self.trim_filled_order_history();
+        }
+    }
+
+    fn trim_filled_order_history(&mut self) {
+        while self.filled_order_history.len() > self.filled_order_history_size {
+            if let Some(order_id) = self.filled_order_history.pop_front() {
+                self.filled_orders.remove(&order_id);
+            }
+        }
+    }
 
+    /// Remember the last `size` filled orders, so cancels and modifications
+    /// of them are rejected as already filled rather than unknown. Defaults
+    /// to 10,000.
+    pub fn set_filled_order_history(&mut self, size: usize) {
+        self.filled_order_history_size = size;
+        self.trim_filled_order_history();
+    }
+
+    /// Exchange-assigned id of the live order `participant` submitted as
+    /// `client_order_id`
+    pub fn find_client_order(&self, participant: Option<ParticipantId>, client_order_id: ClientOrderId) -> Option<OrderId> {
+        self.client_orders.get(&(participant, client_order_id)).copied()
+    }
+
+    /// Live order with the given id, resting in the book or waiting in the stop book
+    pub fn get_order(&self, order_id: OrderId) -> Option<&Order<P, Q>> {
+        self.orders.get(&order_id)
+            .and_then(|handle: &OrderHandle| self.slab.get(*handle))
+            .or_else(|| self.stop_book.get(order_id))
+    }
+
+    fn get_order_participant(&self, order_id: OrderId) -> Option<ParticipantId> {
+        self.get_order(order_id).and_then(|order: &Order<P, Q>| order.get_participant())
+    }
+
+    /// Open orders of `participant`, resting or waiting to trigger, in order id order
+    pub fn get_participant_orders(&self, participant: ParticipantId) -> Vec<&Order<P, Q>> {
+        self.participant_orders.get(&participant)
+            .into_iter()
+            .flatten()
+            .filter_map(|order_id: &OrderId| self.get_order(*order_id))
+            .collect()
+    }
+
+    /// Best price on `side` among non-pegged orders, which pegged orders track
+    fn reference_price(&self, side: Side) -> Option<P> {
+        let has_reference = |level: &PriceLevel| self.slab.iter(level).any(|order: &Order<P, Q>| order.get_peg().is_none() && !auction::is_auction_market_order(order));
         match side {
-            Side::Buy => {
-                self.bids.entry(order.get_price()).or_insert_with(Vec::new).push(order.clone());
+            Side::Buy => self.bids.iter().rev().find(|(_, level)| has_reference(level)).map(|(price, _)| price),
+            Side::Sell => self.asks.iter().find(|(_, level)| has_reference(level)).map(|(price, _)| price),
+        }
+    }
+
+    /// Current pegged price for `order`, rounded onto the tick away from the
+    /// opposite side and kept one tick behind the opposite best so that
+    /// pegging never takes liquidity
+    fn pegged_price(&self, order: &Order<P, Q>) -> Option<P> {
+        let peg: Peg<P> = order.get_peg()?;
+        let side: Side = order.get_side();
+        let target: P = peg.target_price(side, self.reference_price(Side::Buy), self.reference_price(Side::Sell))?;
+
+        let tick_table = &self.instrument_rules.tick_table;
+
+        let price: P = match side {
+            Side::Buy => match self.get_best_ask() {
+                Some(best_ask) if target >= best_ask => tick_table.tick_below(best_ask)?,
+                _ => tick_table.round_down(target),
+            },
+            Side::Sell => match self.get_best_bid() {
+                Some(best_bid) if target <= best_bid => tick_table.tick_above(best_bid)?,
+                _ => tick_table.round_up(target)?,
+            },
+        };
+        self.instrument_rules.is_price_in_range(price).then_some(price)
+    }
+
+    /// Move pegged orders to follow their reference prices.
+    ///
+    /// Reference prices come from non-pegged orders only, so pegged orders
+    /// never chase each other. Priority rule: an order whose pegged price
+    /// changes loses time priority and joins the back of the queue at its new
+    /// price, while an order whose pegged price is unchanged keeps its place.
+    /// Orders whose reference price has gone, or whose new level has no room
+    /// for them, stay where they are. Repricing never crosses the book, so it
+    /// never trades.
+    pub fn reprice_pegged_orders(&mut self) {
+        let order_ids: OrderIds = self.pegged.iter().copied().collect();
+
+        for order_id in order_ids {
+            let Some(order) = self.get_order(order_id) else {
+                continue;
+            };
+            let Some(price) = self.pegged_price(order) else {
+                continue;
+            };
+            if price == order.get_price() || !self.has_level_room(order.get_side(), price, order.get_remaining_quantity()) {
+                continue;
             }
-            Side::Sell => {
-                self.asks.entry(order.get_price()).or_insert_with(Vec::new).push(order.clone());
+
+            if let Some(mut order) = self.unlink_order(order_id) {
+                order.reprice(price);
+                self.rest_order(order);
             }
         }
+    }
 
-        self.orders.insert(order_id, order.clone());
-        self.on_order_added(&order);
+    /// Price one tick behind the opposite best, where a sliding post-only
+    /// order on `side` can rest without crossing
+    fn post_only_slide_price(&self, side: Side) -> Option<P> {
+        let price: P = match side {
+            Side::Buy => self.instrument_rules.tick_table.tick_below(self.get_best_ask()?)?,
+            Side::Sell => self.instrument_rules.tick_table.tick_above(self.get_best_bid()?)?,
+        };
+        self.instrument_rules.is_price_in_range(price).then_some(price)
+    }
 
-        let trades: Trades = self.match_orders();
+    /// Cancel each of `order_ids`, returning a result for each in the same order
+    pub fn cancel_orders(&mut self, order_ids: OrderIds) -> Vec<CancelResult<P, Q>> {
+        if !self.trading_phase.accepts_cancels() {
+            return order_ids.iter().map(|_| Err(CancelRejectReason::MarketClosed)).collect();
+        }
+        let results: Vec<CancelResult<P, Q>> = order_ids.into_iter()
+            .map(|order_id: OrderId| self.cancel_order_internal(order_id))
+            .collect();
+        self.reprice_pegged_orders();
+        results
+    }
 
-        let status: OrderStatus = if trades.is_empty() {
-            OrderStatus::Accepted
+    /// Cancel a resting or untriggered stop order regardless of the trading
+    /// phase, without repricing pegged orders afterwards
+    fn cancel_order_internal(&mut self, order_id: OrderId) -> CancelResult<P, Q> {
+        match self.remove_order(order_id).or_else(|| self.remove_stop_order(order_id)) {
+            Some(order) => Ok(CancelledOrder::new(order)),
+            None => Err(self.missing_order_reason(order_id)),
+        }
+    }
+
+    /// Why no live order with `order_id` could be found
+    fn missing_order_reason(&self, order_id: OrderId) -> CancelRejectReason {
+        if self.filled_orders.contains(&order_id) {
+            CancelRejectReason::AlreadyFilled
         } else {
-            OrderStatus::Executed
-        };
+            CancelRejectReason::UnknownOrder
+        }
+    }
 
-        OrderResult::new(order_id, status, trades)
+    /// Remove an untriggered stop from the stop book, returning it if it was present
+    fn remove_stop_order(&mut self, order_id: OrderId) -> Option<Order<P, Q>> {
+        let order: Order<P, Q> = self.stop_book.remove(order_id)?;
+        self.remove_expiry(&order);
+        self.untrack_live_order(&order);
+        Some(order)
     }
 
-    pub fn cancel_orders(&mut self, order_ids: OrderIds) {
-        for order_id in order_ids {
-            self.cancel_order_internal(order_id);
+    /// Remove a resting order from the book, returning it if it was present
+    fn remove_order(&mut self, order_id: OrderId) -> Option<Order<P, Q>> {
+        let order: Order<P, Q> = self.unlink_order(order_id)?;
+        self.untrack_live_order(&order);
+        Some(order)
+    }
+
+    /// Take a resting order off the book but keep its client order id, for
+    /// orders that are about to rest again at another price
+    fn unlink_order(&mut self, order_id: OrderId) -> Option<Order<P, Q>> {
+        let order: Order<P, Q> = self.take_resting_order(order_id)?;
+        self.on_order_cancelled(&order);
+        self.forget_order(&order);
+        Some(order)
+    }
+
+    /// Take an order out of its level and the slab, leaving every index to the caller
+    fn take_resting_order(&mut self, order_id: OrderId) -> Option<Order<P, Q>> {
+        let handle: OrderHandle = *self.orders.get(&order_id)?;
+        let (side, price) = {
+            let order: &Order<P, Q> = self.slab.get(handle)?;
+            (order.get_side(), order.get_price())
+        };
+        let levels: &mut dyn PriceLadder<P> = match side {
+            Side::Buy => self.bids.as_mut(),
+            Side::Sell => self.asks.as_mut(),
+        };
+        let level: &mut PriceLevel = levels.get_mut(price)?;
+        let order: Order<P, Q> = self.slab.remove(level, handle)?;
+        if level.is_empty() {
+            levels.remove(price);
         }
+        self.orders.remove(&order_id);
+        Some(order)
     }
 
-    pub fn cancel_order_internal(&mut self, order_id: OrderId) {
-        if !self.orders.contains_key(&order_id) {
-            return;
+    /// Cancel a resting or untriggered stop order, returning what was cancelled
+    pub fn cancel_order(&mut self, order_id: OrderId) -> CancelResult<P, Q> {
+        if !self.trading_phase.accepts_cancels() {
+            return Err(CancelRejectReason::MarketClosed);
         }
+        let result: CancelResult<P, Q> = self.cancel_order_internal(order_id);
+        self.reprice_pegged_orders();
+        result
+    }
 
-        let order: Order = self.orders.remove(&order_id).unwrap();
-        match order.side {
-            Side::Buy => {
-                let price: Price = order.price;
-                let orders: &mut Orders = self.bids.get_mut(&price).unwrap();
-                orders.retain(|o: &Order| o.id != order_id);
-                if orders.is_empty() {
-                    self.bids.remove(&price);
+    /// Cancel every resting and untriggered stop order that `filter`
+    /// matches, in one pass over the matching sides and price levels
+    pub fn mass_cancel(&mut self, filter: MassCancel<P>) -> Result<MassCancelSummary<P, Q>, CancelRejectReason> {
+        if !self.trading_phase.accepts_cancels() {
+            return Err(CancelRejectReason::MarketClosed);
+        }
+
+        let (min_price, max_price) = filter.price_range.unwrap_or((P::MIN, P::MAX));
+        let mut removed: Orders<P, Q> = Vec::new();
+        if min_price <= max_price {
+            for side in [Side::Buy, Side::Sell] {
+                if !filter.matches_side(side) {
+                    continue;
                 }
-            }
-            Side::Sell => {
-                let price: Price = order.price;
-                let orders: &mut Orders = self.asks.get_mut(&price).unwrap();
-                orders.retain(|o: &Order| o.id != order_id);
-                if orders.is_empty() {
-                    self.asks.remove(&price);
+                let levels: &mut dyn PriceLadder<P> = match side {
+                    Side::Buy => self.bids.as_mut(),
+                    Side::Sell => self.asks.as_mut(),
+                };
+
+                let prices: Vec<P> = levels.range(min_price, max_price).map(|(price, _)| price).collect();
+                for price in prices {
+                    let Some(level) = levels.get_mut(price) else {
+                        continue;
+                    };
+                    let mut next: Option<OrderHandle> = level.front();
+                    while let Some(handle) = next {
+                        next = self.slab.next(handle);
+                        if self.slab.get(handle).is_some_and(|order: &Order<P, Q>| filter.matches(order)) {
+                            removed.extend(self.slab.remove(level, handle));
+                        }
+                    }
+                    if level.is_empty() {
+                        levels.remove(price);
+                    }
                 }
             }
         }
 
-        self.on_order_cancelled(&order);
+        for order in &removed {
+            self.orders.remove(&order.get_order_id());
+            self.on_order_cancelled(order);
+            self.forget_order(order);
+            self.untrack_live_order(order);
+        }
+
+        let stop_ids: OrderIds = self.stop_book.iter()
+            .filter(|order: &&Order<P, Q>| filter.matches(*order))
+            .map(|order: &Order<P, Q>| order.get_order_id())
+            .collect();
+        removed.extend(stop_ids.into_iter().filter_map(|order_id: OrderId| self.remove_stop_order(order_id)));
+
+        removed.sort_unstable_by_key(|order: &Order<P, Q>| order.get_order_id());
+        self.reprice_pegged_orders();
+        Ok(MassCancelSummary::new(removed.into_iter().map(CancelledOrder::new).collect()))
     }
 
-    pub fn cancel_order(&mut self, order_id: OrderId) {
-        self.cancel_order_internal(order_id);
+    /// Cancel an order on behalf of `participant`, rejecting orders that
+    /// belong to anyone else
+    pub fn cancel_participant_order(&mut self, participant: ParticipantId, order_id: OrderId) -> CancelResult<P, Q> {
+        if !self.trading_phase.accepts_cancels() {
+            return Err(CancelRejectReason::MarketClosed);
+        }
+        match self.get_order(order_id) {
+            Some(order) if order.get_participant() != Some(participant) => Err(CancelRejectReason::WrongOwner),
+            _ => self.cancel_order(order_id),
+        }
     }
 
-    pub fn modify_order(&mut self, order_modify: OrderModify) -> Trades {
-        let order_type: OrderType = {
-            if let Some(existing_order) = self.orders.get(&order_modify.get_order_id()) {
-                existing_order.get_order_type()
+    /// Modify the price and total quantity of a live order, keeping its id.
+    ///
+    /// Reducing the quantity at the same price keeps the order's place in the
+    /// queue. Changing the price or increasing the quantity loses time
+    /// priority: the order is placed again as if new, and may trade. If that
+    /// placement is rejected the original order stays where it was. Untriggered
+    /// stops are updated in the stop book.
+    pub fn modify_order(&mut self, order_modify: OrderModify<P, Q>) -> OrderResult<P, Q> {
+        let order_id: OrderId = order_modify.get_order_id();
+        if let Some(status) = self.phase_rejection() {
+            return OrderResult::new(order_id, status, Vec::new());
+        }
+
+        let Some(existing) = self.get_order(order_id) else {
+            let status: OrderStatus = if self.filled_orders.contains(&order_id) {
+                OrderStatus::RejectedOrderFilled
             } else {
-                return Vec::new();
-            }
+                OrderStatus::RejectedUnknownOrder
+            };
+            return OrderResult::new(order_id, status, Vec::new());
         };
+        if existing.get_side() != order_modify.get_side() {
+            return OrderResult::new(order_id, OrderStatus::RejectedSideChange, Vec::new());
+        }
+        if order_modify.get_quantity() <= existing.filled_quantity {
+            return OrderResult::new(order_id, OrderStatus::RejectedQuantityBelowFilled, Vec::new());
+        }
 
-        self.cancel_order(order_modify.get_order_id());
-        let (_, trades) = self.add_order(
-            order_modify.get_side(),
-            order_type,
-            order_modify.get_price(),
-            order_modify.get_quantity(),
-        );
-        trades
+        let existing_price: P = existing.get_price();
+        let existing_quantity: Q = existing.get_initial_quantity();
+        let mut order: Order<P, Q> = existing.clone();
+        order.price = order_modify.get_price();
+        order.resize(order_modify.get_quantity());
+        if let Some(status) = self.instrument_rules.validate(&order) {
+            return OrderResult::new(order_id, status, Vec::new());
+        }
+
+        if self.stop_book.remove(order_id).is_some() {
+            self.stop_book.insert(order);
+            return OrderResult::new(order_id, OrderStatus::PendingTrigger, Vec::new());
+        }
+
+        let keeps_priority: bool = order.get_price() == existing_price
+            && order.get_initial_quantity() <= existing_quantity;
+        if keeps_priority {
+            self.reduce_order(order);
+            return OrderResult::new(order_id, OrderStatus::Accepted, Vec::new());
+        }
+
+        // The order just ahead in the queue, to put the original back behind
+        let ahead: Option<OrderHandle> = self.orders.get(&order_id).and_then(|handle: &OrderHandle| self.slab.prev(*handle));
+        let Some(original) = self.unlink_order(order_id) else {
+            return OrderResult::new(order_id, OrderStatus::RejectedUnknownOrder, Vec::new());
+        };
+        order.replenish();
+
+        let mut result: OrderResult<P, Q> = self.place_order(order);
+        if result.status.is_rejected() {
+            self.rest_order_after(original, ahead);
+            return result;
+        }
+
+        let triggered_trades: Trades<P, Q> = self.release_triggered_stops();
+        if !triggered_trades.is_empty() {
+            result.trades.extend(triggered_trades);
+            result.status = OrderStatus::Executed;
+        }
+
+        self.reprice_pegged_orders();
+        result
+    }
+
+    /// Shrink a resting order in place, keeping its queue position
+    fn reduce_order(&mut self, order: Order<P, Q>) {
+        let order_id: OrderId = order.get_order_id();
+        let Some(resting) = self.orders.get(&order_id).and_then(|handle: &OrderHandle| self.slab.get_mut(*handle)) else {
+            return;
+        };
+        let reduction: Q = resting.get_remaining_quantity() - order.get_remaining_quantity();
+        let side: Side = order.get_side();
+        let price: P = order.get_price();
+        *resting = order;
+
+        // The order stays in its level, so only the level quantity changes
+        self.update_level_data(side, price, reduction, LevelAction::Match);
     }
 
     pub fn size(&self) -> usize {
         self.orders.len()
     }
 
-    pub fn get_order_infos(&self) -> OrderbookLevelInfos {
+    pub fn get_order_infos(&self) -> OrderbookLevelInfos<P, Q> {
         let mut bid_infos = Vec::new();
         let mut ask_infos = Vec::new();
 
-        for (price, orders) in self.bids.iter().rev() {
-            let total_quantity: Quantity = orders.iter()
-                .map(|order: &Order| order.get_remaining_quantity())
-                .sum();
+        // Only the displayed slice of iceberg orders is published
+        for (price, level) in self.bids.iter().rev() {
+            let total_quantity: Q = self.slab.iter(level)
+                .fold(Q::ZERO, |total: Q, order: &Order<P, Q>| total.saturating_add(order.get_visible_quantity()));
             bid_infos.push(LevelInfo {
-                price: *price,
+                price,
                 quantity: total_quantity,
             });
         }
 
-        for (price, orders) in &self.asks {
-            let total_quantity: u32 = orders.iter()
-                .map(|order: &Order| order.get_remaining_quantity())
-                .sum();
+        for (price, level) in self.asks.iter() {
+            let total_quantity: Q = self.slab.iter(level)
+                .fold(Q::ZERO, |total: Q, order: &Order<P, Q>| total.saturating_add(order.get_visible_quantity()));
             ask_infos.push(LevelInfo {
-                price: *price,
+                price,
                 quantity: total_quantity,
             });
         }
@@ -497,26 +1184,383 @@ impl OrderBook {
         OrderbookLevelInfos::new(bid_infos, ask_infos)
     }
 
-    pub fn get_best_bid(&self) -> Option<Price> {
-        self.bids.keys().next_back().copied()
+    /// Aggregated depth of one side of the book
+    pub fn get_depth(&self, side: Side) -> &Depth<P, Q> {
+        match side {
+            Side::Buy => &self.bid_depth,
+            Side::Sell => &self.ask_depth,
+        }
     }
 
-    pub fn get_best_ask(&self) -> Option<Price> {
-        self.asks.keys().next().copied()
+    /// Resting quantity an order on `side` limited at `price` could trade
+    /// against, counting hidden iceberg quantity
+    pub fn get_available_liquidity(&self, side: Side, price: P) -> Q {
+        self.get_depth(side.opposite()).get_liquidity_up_to(price)
     }
 
-    /// Prune Good For Day orders
-    pub fn prune_good_for_day_orders(&mut self) {
-        let mut orders_to_cancel = Vec::new();
+    pub fn get_best_bid(&self) -> Option<P> {
+        self.bids.max_price()
+    }
 
-        for (order_id, order) in &self.orders {
-            if order.get_order_type() == OrderType::GoodForDay {
-                // In a for real implementation, check if it's past market close time
-                // For now, we just collect them for potential cancellation
-                orders_to_cancel.push(*order_id);
+    pub fn get_best_ask(&self) -> Option<P> {
+        self.asks.min_price()
+    }
+
+    /// Price of the most recent trade, which drives stop triggering
+    pub fn get_last_trade_price(&self) -> Option<P> {
+        self.last_trade_price
+    }
+
+    pub fn get_trading_phase(&self) -> TradingPhase {
+        self.trading_phase
+    }
+
+    /// Move the session to `phase`, publishing a phase change event. Entering
+    /// pre-open starts the opening auction and leaving it for open uncrosses
+    /// it, returning the auction trades. Returns None if the transition is
+    /// not allowed.
+    pub fn set_trading_phase(&mut self, phase: TradingPhase) -> Option<Trades<P, Q>> {
+        let from: TradingPhase = self.trading_phase;
+        if from == phase {
+            return Some(Vec::new());
+        }
+        if !from.can_transition_to(phase) {
+            return None;
+        }
+
+        self.trading_phase = phase;
+        self.events.push(BookEvent::PhaseChanged(PhaseChanged {
+            from,
+            to: phase,
+            timestamp: self.clock.now(),
+        }));
+
+        match (from, phase) {
+            (_, TradingPhase::PreOpen) => {
+                self.open_auction();
+                Some(Vec::new())
+            }
+            // Stops triggered during a halt may have left the book crossed
+            (TradingPhase::PreOpen | TradingPhase::Halted, TradingPhase::Open) => Some(self.uncross()),
+            (TradingPhase::PreOpen, _) => {
+                self.auction = false;
+                Some(Vec::new())
+            }
+            _ => Some(Vec::new()),
+        }
+    }
+
+    /// Take every event published since the last drain, oldest first
+    pub fn drain_events(&mut self) -> Vec<BookEvent<P, Q>> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Status an order or modification is rejected with in the current phase
+    fn phase_rejection(&self) -> Option<OrderStatus> {
+        if self.trading_phase.accepts_orders() {
+            return None;
+        }
+        match self.trading_phase {
+            TradingPhase::Halted => Some(OrderStatus::RejectedTradingHalted),
+            _ => Some(OrderStatus::RejectedMarketClosed),
+        }
+    }
+
+    /// Set the tick size, lot size and order size limits orders are checked against
+    pub fn set_instrument_rules(&mut self, rules: InstrumentRules<P, Q>) {
+        self.instrument_rules = rules;
+    }
+
+    pub fn get_instrument_rules(&self) -> &InstrumentRules<P, Q> {
+        &self.instrument_rules
+    }
+
+    /// Pause trading whenever an execution would fall outside `band`
+    pub fn set_price_band(&mut self, band: PriceBand<P>) {
+        self.price_band = Some(band);
+    }
+
+    pub fn get_price_band(&self) -> Option<PriceBand<P>> {
+        self.price_band
+    }
+
+    /// The price band and the reference price it is currently centred on
+    fn active_price_band(&self) -> Option<(PriceBand<P>, P)> {
+        let band: PriceBand<P> = self.price_band?;
+        let reference_price: P = band.reference_price.or(self.last_trade_price)?;
+        Some((band, reference_price))
+    }
+
+    /// Worst price an order on `side` limited at `price` can trade at without
+    /// breaching the price band
+    fn band_capped_price(&self, side: Side, price: P) -> P {
+        let Some((band, reference_price)) = self.active_price_band() else {
+            return price;
+        };
+        let (low, high) = band.limits(reference_price);
+        match side {
+            Side::Buy => price.min(high),
+            Side::Sell => price.max(low),
+        }
+    }
+
+    /// Stop matching after `order_id` would have traded at `price` outside the band
+    fn pause_trading(&mut self, order_id: OrderId, price: P, reference_price: P, action: BreachAction) {
+        self.events.push(BookEvent::TradingPaused(TradingPaused {
+            order_id,
+            price,
+            reference_price,
+            action,
+            timestamp: self.clock.now(),
+        }));
+
+        match action {
+            BreachAction::Halt => {
+                self.set_trading_phase(TradingPhase::Halted);
+            }
+            BreachAction::VolatilityAuction => self.open_auction(),
+        }
+    }
+
+    /// Publish that the book cancelled `order` on its own
+    fn publish_cancel(&mut self, order: &Order<P, Q>, reason: CancelReason) {
+        let timestamp: Timestamp = self.clock.now();
+        self.events.push(BookEvent::OrderCancelled(OrderCancelled::new(order, reason, timestamp)));
+    }
+
+    /// Start a call auction. Orders accumulate without matching, crossed or
+    /// not, until the auction is uncrossed.
+    pub fn open_auction(&mut self) {
+        self.auction = true;
+    }
+
+    pub fn is_auction(&self) -> bool {
+        self.auction
+    }
+
+    /// Price, volume and imbalance the open auction would uncross at now
+    pub fn get_indicative_uncross(&self) -> Option<AuctionUncross<P, Q>> {
+        if !self.auction {
+            return None;
+        }
+        auction::equilibrium(&self.auction_levels(Side::Buy), &self.auction_levels(Side::Sell), self.last_trade_price)
+    }
+
+    fn auction_levels(&self, side: Side) -> Vec<AuctionLevel<P>> {
+        self.levels(side).iter()
+            .map(|(price, level)| AuctionLevel::from_orders(price, self.slab.iter(level)))
+            .collect()
+    }
+
+    /// End the auction: execute every crossing order at the single
+    /// equilibrium price and return to continuous matching. Market orders
+    /// left over rest as limit orders at that price, or are cancelled if
+    /// nothing executed or that level has no room for them. Uncrossing the
+    /// pre-open auction opens the market. An equilibrium price outside the
+    /// price band pauses trading again instead of executing.
+    pub fn uncross_auction(&mut self) -> Trades<P, Q> {
+        if !self.auction {
+            return Vec::new();
+        }
+        if self.trading_phase == TradingPhase::PreOpen {
+            return self.set_trading_phase(TradingPhase::Open).unwrap_or_default();
+        }
+        self.uncross()
+    }
+
+    fn uncross(&mut self) -> Trades<P, Q> {
+        let uncross: Option<AuctionUncross<P, Q>> = auction::equilibrium(&self.auction_levels(Side::Buy), &self.auction_levels(Side::Sell), self.last_trade_price);
+        if let Some(uncross) = uncross
+            && let Some((band, reference_price)) = self.active_price_band()
+            && !band.contains(reference_price, uncross.price)
+            && let Some(order_id) = self.band_breaching_order(uncross.price, reference_price)
+        {
+            self.pause_trading(order_id, uncross.price, reference_price, band.action);
+            return Vec::new();
+        }
+        self.auction = false;
+
+        let mut trades: Trades<P, Q> = match uncross {
+            Some(uncross) => self.execute_uncross(uncross.price, uncross.matched_quantity),
+            None => Vec::new(),
+        };
+
+        let mut market_orders: OrderIds = self.resting_orders()
+            .filter(|order: &&Order<P, Q>| auction::is_auction_market_order(order))
+            .map(|order: &Order<P, Q>| order.get_order_id())
+            .collect();
+        market_orders.sort_unstable();
+        for order_id in market_orders {
+            if let Some(mut order) = self.unlink_order(order_id) {
+                match uncross {
+                    Some(uncross) if self.has_level_room(order.get_side(), uncross.price, order.get_remaining_quantity()) => {
+                        order.to_limit(uncross.price);
+                        self.rest_order(order);
+                    }
+                    _ => {
+                        self.untrack_live_order(&order);
+                        self.publish_cancel(&order, CancelReason::AuctionUnfilled);
+                    }
+                }
+            }
+        }
+
+        trades.extend(self.release_triggered_stops());
+        self.reprice_pegged_orders();
+        trades
+    }
+
+    /// Front order of the side that pushes an uncross at `price` away from
+    /// `reference_price`
+    fn band_breaching_order(&self, price: P, reference_price: P) -> Option<OrderId> {
+        let (side, best_price): (Side, Option<P>) = if price > reference_price {
+            (Side::Buy, self.get_best_bid())
+        } else {
+            (Side::Sell, self.get_best_ask())
+        };
+        self.get_level_orders(side, best_price?).next().map(|order: &Order<P, Q>| order.get_order_id())
+    }
+
+    /// Trade `quantity` at `price` between the crossing bids and asks, each
+    /// side filled in price-time priority
+    fn execute_uncross(&mut self, price: P, quantity: Q) -> Trades<P, Q> {
+        let bid_fills: Vec<(OrderId, Q)> = self.uncross_fills(self.bids.range(price, P::MAX).rev(), quantity);
+        let ask_fills: Vec<(OrderId, Q)> = self.uncross_fills(self.asks.range(P::MIN, price), quantity);
+
+        let mut trades: Trades<P, Q> = Vec::new();
+        let mut bids = bid_fills.iter().copied();
+        let mut asks = ask_fills.iter().copied();
+        let mut bid: Option<(OrderId, Q)> = bids.next();
+        let mut ask: Option<(OrderId, Q)> = asks.next();
+
+        while let (Some((bid_id, bid_quantity)), Some((ask_id, ask_quantity))) = (bid, ask) {
+            let traded: Q = bid_quantity.min(ask_quantity);
+            trades.push(Trade::new(
+                TradeInfo::new(bid_id, price, traded).with_participant(self.get_order_participant(bid_id)),
+                TradeInfo::new(ask_id, price, traded).with_participant(self.get_order_participant(ask_id)),
+            ));
+
+            bid = if bid_quantity == traded { bids.next() } else { Some((bid_id, bid_quantity - traded)) };
+            ask = if ask_quantity == traded { asks.next() } else { Some((ask_id, ask_quantity - traded)) };
+        }
+
+        for (order_id, filled) in bid_fills.into_iter().chain(ask_fills) {
+            self.fill_resting_order(order_id, filled);
+        }
+        if !trades.is_empty() {
+            self.record_trade_price(price);
+        }
+
+        trades
+    }
+
+    /// How much of `quantity` each order in `levels` receives, in the order given
+    fn uncross_fills<'a>(&self, levels: impl Iterator<Item = (P, &'a PriceLevel)>, mut quantity: Q) -> Vec<(OrderId, Q)> {
+        let mut fills: Vec<(OrderId, Q)> = Vec::new();
+        for order in levels.flat_map(|(_, level)| self.slab.iter(level)) {
+            if quantity == Q::ZERO {
+                break;
             }
+            let filled: Q = quantity.min(order.get_remaining_quantity());
+            fills.push((order.get_order_id(), filled));
+            quantity -= filled;
         }
-        // Only cancel if past market close
-        // self.cancel_orders(&orders_to_cancel);
+        fills
     }
-}
\ No newline at end of file
+
+    /// Fill a resting order in place, removing it from the book once complete
+    fn fill_resting_order(&mut self, order_id: OrderId, quantity: Q) {
+        let Some(order) = self.orders.get(&order_id).and_then(|handle: &OrderHandle| self.slab.get_mut(*handle)) else {
+            return;
+        };
+        order.fill(quantity);
+        if order.needs_replenish() {
+            order.replenish();
+        }
+        let side: Side = order.get_side();
+        let price: P = order.get_price();
+        let filled: bool = order.is_filled();
+
+        self.on_order_matched(side, price, quantity, filled);
+        if filled && let Some(order) = self.take_resting_order(order_id) {
+            self.forget_order(&order);
+            self.untrack_live_order(&order);
+            self.remember_filled_order(order_id);
+        }
+    }
+
+    /// Cancel every Good For Day order once the clock has reached market
+    /// close, reporting a cancellation for each. Does nothing before close
+    /// or when no market schedule is set.
+    pub fn prune_good_for_day_orders(&mut self) -> Vec<OrderCancelled<P, Q>> {
+        let now: Timestamp = self.clock.now();
+        let (Some(schedule), Some(market_close)) = (self.market_schedule, self.next_market_close) else {
+            return Vec::new();
+        };
+        if now < market_close {
+            return Vec::new();
+        }
+        self.next_market_close = Some(schedule.next_close_after(now));
+
+        let mut orders_to_cancel: OrderIds = self.resting_orders()
+            .chain(self.stop_book.iter())
+            .filter(|order: &&Order<P, Q>| order.get_time_in_force() == TimeInForce::GoodForDay)
+            .map(|order: &Order<P, Q>| order.get_order_id())
+            .collect();
+        orders_to_cancel.sort_unstable();
+
+        let cancelled: Vec<OrderCancelled<P, Q>> = orders_to_cancel.into_iter()
+            .filter_map(|order_id: OrderId| self.remove_order(order_id).or_else(|| self.remove_stop_order(order_id)))
+            .map(|order: Order<P, Q>| OrderCancelled::new(&order, CancelReason::MarketClose, market_close))
+            .collect();
+        self.reprice_pegged_orders();
+        cancelled
+    }
+
+    /// Cancel every Good Till Date order whose expiry has been reached,
+    /// reporting a cancellation for each in expiry order
+    pub fn expire_orders(&mut self) -> Vec<OrderCancelled<P, Q>> {
+        let now: Timestamp = self.clock.now();
+        let expired: Vec<Timestamp> = self.expiries.range(..=now).map(|(expiry, _)| *expiry).collect();
+
+        let mut cancelled: Vec<OrderCancelled<P, Q>> = Vec::new();
+        for expiry in expired {
+            let order_ids: OrderIds = self.expiries.remove(&expiry).unwrap_or_default();
+            for order_id in order_ids {
+                if let Some(order) = self.remove_order(order_id).or_else(|| self.remove_stop_order(order_id)) {
+                    cancelled.push(OrderCancelled::new(&order, CancelReason::Expired, expiry));
+                }
+            }
+        }
+        self.reprice_pegged_orders();
+        cancelled
+    }
+
+    /// Apply everything due at the clock's current time: Good Till Date
+    /// expiries first, then the Good For Day market close
+    pub fn process_expirations(&mut self) -> Vec<OrderCancelled<P, Q>> {
+        let mut cancelled: Vec<OrderCancelled<P, Q>> = self.expire_orders();
+        cancelled.extend(self.prune_good_for_day_orders());
+        cancelled
+    }
+
+    fn add_expiry(&mut self, order: &Order<P, Q>) {
+        if order.get_time_in_force() == TimeInForce::GoodTillDate
+            && let Some(expiry) = order.get_expiry()
+        {
+            self.expiries.entry(expiry).or_default().push(order.get_order_id());
+        }
+    }
+
+    fn remove_expiry(&mut self, order: &Order<P, Q>) {
+        let Some(expiry) = order.get_expiry() else {
+            return;
+        };
+        if let Some(order_ids) = self.expiries.get_mut(&expiry) {
+            order_ids.retain(|order_id: &OrderId| *order_id != order.get_order_id());
+            if order_ids.is_empty() {
+                self.expiries.remove(&expiry);
+            }
+        }
+    }
+}
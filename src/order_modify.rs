@@ -1,15 +1,16 @@
-use crate::{Order, OrderId, OrderType, Price, Quantity, Side};
+use crate::{Order, OrderId, OrdType, Price, Quantity, Side, TimeInForce};
+use crate::numeric::{PriceValue, QuantityValue};
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct OrderModify {
+pub struct OrderModify<P = Price, Q = Quantity> {
     pub order_id: OrderId,
     pub side: Side,
-    pub price: Price,
-    pub quantity: Quantity,
+    pub price: P,
+    pub quantity: Q,
 }
 
-impl OrderModify {
-    pub fn new(order_id: OrderId, side: Side, price: Price, quantity: Quantity) -> Self {
+impl<P: PriceValue, Q: QuantityValue> OrderModify<P, Q> {
+    pub fn new(order_id: OrderId, side: Side, price: P, quantity: Q) -> Self {
         Self {
             order_id,
             side,
@@ -26,16 +27,16 @@ impl OrderModify {
         self.side
     }
 
-    pub fn get_price(&self) -> Price {
+    pub fn get_price(&self) -> P {
         self.price
     }
 
-    pub fn get_quantity(&self) -> Quantity {
+    pub fn get_quantity(&self) -> Q {
         self.quantity
     }
 
     /// Convert OrderModify to a new Order
-    pub fn to_order(&self, order_type: OrderType) -> Order {
-        Order::new(self.order_id, self.side, order_type, self.price, self.quantity)
+    pub fn to_order(&self, ord_type: OrdType, time_in_force: TimeInForce) -> Order<P, Q> {
+        Order::new(self.order_id, self.side, ord_type, time_in_force, self.price, self.quantity)
     }
 }
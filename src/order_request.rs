@@ -0,0 +1,170 @@
+use crate::{ClientOrderId, Order, OrderId, ParticipantId, OrdType, TimeInForce, Peg, PostOnly, Price, Quantity, Side, Timestamp, TrailAmount};
+use crate::numeric::{PriceValue, QuantityValue};
+
+/// A request to place a new order, before the book has assigned it an id
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderRequest<P = Price, Q = Quantity> {
+    pub side: Side,
+    pub ord_type: OrdType,
+    pub time_in_force: TimeInForce,
+    pub price: P,
+    pub quantity: Q,
+    pub stop_price: Option<P>,
+    pub expiry: Option<Timestamp>,
+    pub display_quantity: Option<Q>,
+    pub post_only: Option<PostOnly>,
+    pub peg: Option<Peg<P>>,
+    pub trail: Option<TrailAmount<P>>,
+    pub lead_market_maker: bool,
+    pub participant: Option<ParticipantId>,
+    pub client_order_id: Option<ClientOrderId>,
+}
+
+impl<P: PriceValue, Q: QuantityValue> OrderRequest<P, Q> {
+    pub fn new(side: Side, ord_type: OrdType, time_in_force: TimeInForce, price: P, quantity: Q) -> Self {
+        Self {
+            side,
+            ord_type,
+            time_in_force,
+            price,
+            quantity,
+            stop_price: None,
+            expiry: None,
+            display_quantity: None,
+            post_only: None,
+            peg: None,
+            trail: None,
+            lead_market_maker: false,
+            participant: None,
+            client_order_id: None,
+        }
+    }
+
+    /// Set the trigger price for Stop and StopLimit orders
+    pub fn with_stop_price(mut self, stop_price: P) -> Self {
+        self.stop_price = Some(stop_price);
+        self
+    }
+
+    /// Set the expiry timestamp for Good Till Date orders
+    pub fn with_expiry(mut self, expiry: Timestamp) -> Self {
+        self.expiry = Some(expiry);
+        self
+    }
+
+    /// Make the order an iceberg showing at most `display_quantity` at a time
+    pub fn with_display_quantity(mut self, display_quantity: Q) -> Self {
+        self.display_quantity = Some(display_quantity);
+        self
+    }
+
+    /// Never take liquidity; `mode` decides what happens if the order would cross
+    pub fn with_post_only(mut self, mode: PostOnly) -> Self {
+        self.post_only = Some(mode);
+        self
+    }
+
+    /// Peg the order's price to a reference price; the request price is ignored
+    pub fn with_peg(mut self, peg: Peg<P>) -> Self {
+        self.peg = Some(peg);
+        self
+    }
+
+    /// Make a Stop order trail the last trade price by `trail`
+    pub fn with_trailing_stop(mut self, trail: TrailAmount<P>) -> Self {
+        self.trail = Some(trail);
+        self
+    }
+
+    /// Mark the order as a quote from the designated lead market maker
+    pub fn with_lead_market_maker(mut self) -> Self {
+        self.lead_market_maker = true;
+        self
+    }
+
+    /// Set the participant the order belongs to
+    pub fn with_participant(mut self, participant: ParticipantId) -> Self {
+        self.participant = Some(participant);
+        self
+    }
+
+    /// Set the participant's own id for the order. The book rejects a
+    /// client order id the same participant is already using.
+    pub fn with_client_order_id(mut self, client_order_id: ClientOrderId) -> Self {
+        self.client_order_id = Some(client_order_id);
+        self
+    }
+
+    pub fn get_side(&self) -> Side {
+        self.side
+    }
+
+    pub fn get_ord_type(&self) -> OrdType {
+        self.ord_type
+    }
+
+    pub fn get_time_in_force(&self) -> TimeInForce {
+        self.time_in_force
+    }
+
+    pub fn get_price(&self) -> P {
+        self.price
+    }
+
+    pub fn get_quantity(&self) -> Q {
+        self.quantity
+    }
+
+    pub fn get_stop_price(&self) -> Option<P> {
+        self.stop_price
+    }
+
+    pub fn get_expiry(&self) -> Option<Timestamp> {
+        self.expiry
+    }
+
+    pub fn get_display_quantity(&self) -> Option<Q> {
+        self.display_quantity
+    }
+
+    pub fn get_post_only(&self) -> Option<PostOnly> {
+        self.post_only
+    }
+
+    pub fn get_peg(&self) -> Option<Peg<P>> {
+        self.peg
+    }
+
+    pub fn get_trail(&self) -> Option<TrailAmount<P>> {
+        self.trail
+    }
+
+    pub fn is_lead_market_maker(&self) -> bool {
+        self.lead_market_maker
+    }
+
+    pub fn get_participant(&self) -> Option<ParticipantId> {
+        self.participant
+    }
+
+    pub fn get_client_order_id(&self) -> Option<ClientOrderId> {
+        self.client_order_id
+    }
+
+    /// Convert the request into an Order with the given id
+    pub fn to_order(&self, order_id: OrderId) -> Order<P, Q> {
+        let mut order: Order<P, Q> = Order::new(order_id, self.side, self.ord_type, self.time_in_force, self.price, self.quantity);
+        order.stop_price = self.stop_price;
+        order.expiry = self.expiry;
+        order.post_only = self.post_only;
+        order.peg = self.peg;
+        order.trail = self.trail;
+        order.lead_market_maker = self.lead_market_maker;
+        order.participant = self.participant;
+        order.client_order_id = self.client_order_id;
+        if let Some(display_quantity) = self.display_quantity {
+            order.set_display_quantity(display_quantity);
+        }
+        order
+    }
+}
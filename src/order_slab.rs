@@ -0,0 +1,225 @@
+//! Resting orders stored in a slab and linked into one queue per price level.
+//!
+//! Each order sits in a slot of the slab together with links to its
+//! neighbours at the same price, so appending, removing and moving an order
+//! to the back of its level are all O(1) given its handle. Freed slots are
+//! reused by later orders.
+
+use crate::{
+    order::Order,
+    types::{Price, Quantity},
+};
+
+/// Index of an order's slot in the slab
+pub type OrderHandle = usize;
+
+/// Queue of orders at one price, in time priority. The orders themselves
+/// live in the `OrderSlab`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriceLevel {
+    head: Option<OrderHandle>,
+    tail: Option<OrderHandle>,
+    len: usize,
+}
+
+impl PriceLevel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Oldest order at the level
+    pub fn front(&self) -> Option<OrderHandle> {
+        self.head
+    }
+
+    /// Newest order at the level
+    pub fn back(&self) -> Option<OrderHandle> {
+        self.tail
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node<P, Q> {
+    order: Order<P, Q>,
+    prev: Option<OrderHandle>,
+    next: Option<OrderHandle>,
+}
+
+/// Storage for resting orders, linked into `PriceLevel` queues
+#[derive(Debug, Clone)]
+pub struct OrderSlab<P = Price, Q = Quantity> {
+    nodes: Vec<Option<Node<P, Q>>>,
+    free: Vec<OrderHandle>,
+    len: usize,
+}
+
+impl<P, Q> Default for OrderSlab<P, Q> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<P, Q> OrderSlab<P, Q> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of orders stored
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, handle: OrderHandle) -> Option<&Order<P, Q>> {
+        self.node(handle).map(|node: &Node<P, Q>| &node.order)
+    }
+
+    pub fn get_mut(&mut self, handle: OrderHandle) -> Option<&mut Order<P, Q>> {
+        self.node_mut(handle).map(|node: &mut Node<P, Q>| &mut node.order)
+    }
+
+    /// Order after `handle` in its level
+    pub fn next(&self, handle: OrderHandle) -> Option<OrderHandle> {
+        self.node(handle)?.next
+    }
+
+    /// Order before `handle` in its level
+    pub fn prev(&self, handle: OrderHandle) -> Option<OrderHandle> {
+        self.node(handle)?.prev
+    }
+
+    /// Add `order` at the back of `level`
+    pub fn push_back(&mut self, level: &mut PriceLevel, order: Order<P, Q>) -> OrderHandle {
+        let after: Option<OrderHandle> = level.tail;
+        self.insert_after(level, after, order)
+    }
+
+    /// Add `order` to `level` right after `after`, or at the front when
+    /// `after` is None
+    pub fn insert_after(&mut self, level: &mut PriceLevel, after: Option<OrderHandle>, order: Order<P, Q>) -> OrderHandle {
+        let next: Option<OrderHandle> = match after {
+            Some(after) => self.next(after),
+            None => level.head,
+        };
+        let node: Node<P, Q> = Node { order, prev: after, next };
+        let handle: OrderHandle = match self.free.pop() {
+            Some(handle) => {
+                self.nodes[handle] = Some(node);
+                handle
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        };
+
+        self.link(level, handle);
+        self.len += 1;
+        handle
+    }
+
+    /// Take an order out of `level` and free its slot
+    pub fn remove(&mut self, level: &mut PriceLevel, handle: OrderHandle) -> Option<Order<P, Q>> {
+        self.node(handle)?;
+        self.unlink(level, handle);
+        let node: Node<P, Q> = self.nodes[handle].take()?;
+        self.free.push(handle);
+        self.len -= 1;
+        Some(node.order)
+    }
+
+    /// Send an order to the back of `level`, behind every other order there
+    pub fn move_to_back(&mut self, level: &mut PriceLevel, handle: OrderHandle) {
+        if self.node(handle).is_none() || level.tail == Some(handle) {
+            return;
+        }
+        self.unlink(level, handle);
+        if let Some(node) = self.node_mut(handle) {
+            node.prev = level.tail;
+            node.next = None;
+        }
+        self.link(level, handle);
+    }
+
+    /// Orders in `level`, front to back
+    pub fn iter(&self, level: &PriceLevel) -> LevelIter<'_, P, Q> {
+        LevelIter {
+            slab: self,
+            next: level.head,
+        }
+    }
+
+    fn node(&self, handle: OrderHandle) -> Option<&Node<P, Q>> {
+        self.nodes.get(handle)?.as_ref()
+    }
+
+    fn node_mut(&mut self, handle: OrderHandle) -> Option<&mut Node<P, Q>> {
+        self.nodes.get_mut(handle)?.as_mut()
+    }
+
+    /// Point the neighbours recorded in the node at `handle` back at it
+    fn link(&mut self, level: &mut PriceLevel, handle: OrderHandle) {
+        let (prev, next) = match self.node(handle) {
+            Some(node) => (node.prev, node.next),
+            None => return,
+        };
+        match prev.and_then(|prev: OrderHandle| self.node_mut(prev)) {
+            Some(prev) => prev.next = Some(handle),
+            None => level.head = Some(handle),
+        }
+        match next.and_then(|next: OrderHandle| self.node_mut(next)) {
+            Some(next) => next.prev = Some(handle),
+            None => level.tail = Some(handle),
+        }
+        level.len += 1;
+    }
+
+    /// Join the neighbours of the node at `handle` to each other
+    fn unlink(&mut self, level: &mut PriceLevel, handle: OrderHandle) {
+        let (prev, next) = match self.node(handle) {
+            Some(node) => (node.prev, node.next),
+            None => return,
+        };
+        match prev.and_then(|prev: OrderHandle| self.node_mut(prev)) {
+            Some(prev) => prev.next = next,
+            None => level.head = next,
+        }
+        match next.and_then(|next: OrderHandle| self.node_mut(next)) {
+            Some(next) => next.prev = prev,
+            None => level.tail = prev,
+        }
+        level.len -= 1;
+    }
+}
+
+/// Iterator over the orders of one level, front to back
+#[derive(Debug, Clone)]
+pub struct LevelIter<'a, P, Q> {
+    slab: &'a OrderSlab<P, Q>,
+    next: Option<OrderHandle>,
+}
+
+impl<'a, P, Q> Iterator for LevelIter<'a, P, Q> {
+    type Item = &'a Order<P, Q>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node: &'a Node<P, Q> = self.slab.node(self.next?)?;
+        self.next = node.next;
+        Some(&node.order)
+    }
+}
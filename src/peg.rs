@@ -0,0 +1,71 @@
+//! Pegged order prices.
+
+use crate::{
+    numeric::PriceValue,
+    types::{Price, Side},
+};
+
+/// Reference price a pegged order tracks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PegType {
+    /// Same side best: bids peg to the best bid, asks to the best ask
+    Primary,
+    /// Opposite side best: bids peg to the best ask, asks to the best bid
+    Market,
+    /// Midpoint of the best bid and best ask, rounded away from the opposite side
+    Midpoint,
+}
+
+/// Pegging instructions for an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Peg<P = Price> {
+    pub peg_type: PegType,
+    /// Added to the reference price
+    pub offset: i64,
+    /// Worst price the order may reach: a ceiling for bids, a floor for asks
+    pub cap: Option<P>,
+}
+
+impl<P: PriceValue> Peg<P> {
+    pub fn new(peg_type: PegType) -> Self {
+        Self {
+            peg_type,
+            offset: 0,
+            cap: None,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: i64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: P) -> Self {
+        self.cap = Some(cap);
+        self
+    }
+
+    /// Price for a pegged order on `side` given the reference best bid and
+    /// ask, or None when the reference price is unavailable
+    pub fn target_price(&self, side: Side, best_bid: Option<P>, best_ask: Option<P>) -> Option<P> {
+        let reference: i128 = match (self.peg_type, side) {
+            (PegType::Primary, Side::Buy) | (PegType::Market, Side::Sell) => best_bid?.to_i128(),
+            (PegType::Primary, Side::Sell) | (PegType::Market, Side::Buy) => best_ask?.to_i128(),
+            (PegType::Midpoint, _) => {
+                let total: i128 = best_bid?.to_i128() + best_ask?.to_i128();
+                match side {
+                    Side::Buy => total.div_euclid(2),
+                    Side::Sell => total.div_euclid(2) + total.rem_euclid(2),
+                }
+            }
+        };
+
+        let price: P = P::from_i128(reference + self.offset as i128)?;
+
+        Some(match (side, self.cap) {
+            (Side::Buy, Some(cap)) => price.min(cap),
+            (Side::Sell, Some(cap)) => price.max(cap),
+            (_, None) => price,
+        })
+    }
+}
@@ -0,0 +1,84 @@
+//! Fixed-point decimal prices.
+
+use crate::{
+    numeric::PriceValue,
+    types::Price,
+};
+
+/// Number of decimal places a raw `Price` carries for an instrument.
+///
+/// With a scale of 2 the raw price `-1234` is `-12.34`; with a scale of 0
+/// raw prices are whole numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PriceScale {
+    decimals: u32,
+}
+
+impl PriceScale {
+    /// Largest supported number of decimal places
+    pub const MAX_DECIMALS: u32 = 18;
+
+    /// Scales above `MAX_DECIMALS` are treated as `MAX_DECIMALS`
+    pub fn new(decimals: u32) -> Self {
+        Self {
+            decimals: decimals.min(Self::MAX_DECIMALS),
+        }
+    }
+
+    pub fn get_decimals(&self) -> u32 {
+        self.decimals
+    }
+
+    /// Raw value of one whole unit, e.g. 100 for a scale of 2
+    pub fn unit(&self) -> Price {
+        10_i64.pow(self.decimals)
+    }
+
+    /// Parse a decimal string such as `"-12.5"` into a raw price. Returns
+    /// None if the string is malformed, has more decimal places than the
+    /// scale, or is out of range for `P`.
+    pub fn parse<P: PriceValue>(&self, text: &str) -> Option<P> {
+        let text: &str = text.trim();
+        let (negative, digits): (bool, &str) = match text.as_bytes().first()? {
+            b'-' => (true, &text[1..]),
+            b'+' => (false, &text[1..]),
+            _ => (false, text),
+        };
+        let (whole, fraction): (&str, &str) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let is_digits = |part: &str| part.bytes().all(|byte: u8| byte.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !is_digits(whole) || !is_digits(fraction) {
+            return None;
+        }
+        if fraction.len() > self.decimals as usize {
+            return None;
+        }
+
+        let whole: i128 = if whole.is_empty() { 0 } else { whole.parse().ok()? };
+        let fraction_value: i128 = if fraction.is_empty() { 0 } else { fraction.parse().ok()? };
+        let padding: u32 = self.decimals - fraction.len() as u32;
+
+        let raw: i128 = whole.checked_mul(self.unit() as i128)?
+            .checked_add(fraction_value * 10_i128.pow(padding))?;
+        P::from_i128(if negative { -raw } else { raw })
+    }
+
+    /// Format a raw price as a decimal string with exactly `decimals` places
+    pub fn format<P: PriceValue>(&self, price: P) -> String {
+        let price: i128 = price.to_i128();
+        let sign: &str = if price < 0 { "-" } else { "" };
+        let magnitude: u128 = price.unsigned_abs();
+        if self.decimals == 0 {
+            return format!("{}{}", sign, magnitude);
+        }
+
+        let unit: u128 = self.unit() as u128;
+        format!(
+            "{}{}.{:0width$}",
+            sign,
+            magnitude / unit,
+            magnitude % unit,
+            width = self.decimals as usize
+        )
+    }
+}
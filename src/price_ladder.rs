@@ -0,0 +1,404 @@
+//! Storage of the price levels of one side of the book.
+//!
+//! `OrderBook` reaches its levels through the `PriceLadder` trait, so the
+//! backend can be picked per book without changing its behaviour.
+//! `TreeLadder` keeps levels in a `BTreeMap` and suits any price range.
+//! `ArrayLadder` indexes levels in a contiguous array by tick offset and finds
+//! the next occupied level with bitmaps, which suits instruments trading in a
+//! known, bounded price band.
+
+use std::{
+    collections::{btree_map, BTreeMap},
+    fmt::Debug,
+};
+use crate::{
+    numeric::PriceValue,
+    order_slab::PriceLevel,
+    types::Price,
+};
+
+/// Iterator over `(price, level)` pairs, lowest price first
+pub type Levels<'a, P> = Box<dyn DoubleEndedIterator<Item = (P, &'a PriceLevel)> + 'a>;
+
+/// Price levels of one side of the book, keyed by price
+pub trait PriceLadder<P: PriceValue = Price>: Debug {
+    /// Number of levels
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, price: P) -> Option<&PriceLevel>;
+
+    fn get_mut(&mut self, price: P) -> Option<&mut PriceLevel>;
+
+    /// Level at `price`, added empty if there is none
+    fn entry(&mut self, price: P) -> &mut PriceLevel;
+
+    fn remove(&mut self, price: P) -> Option<PriceLevel>;
+
+    /// Lowest price with a level
+    fn min_price(&self) -> Option<P>;
+
+    /// Highest price with a level
+    fn max_price(&self) -> Option<P>;
+
+    /// Levels priced from `min_price` to `max_price` inclusive, lowest first
+    fn range(&self, min_price: P, max_price: P) -> Levels<'_, P>;
+
+    /// Every level, lowest price first
+    fn iter(&self) -> Levels<'_, P> {
+        self.range(P::MIN, P::MAX)
+    }
+}
+
+/// Levels kept in a `BTreeMap`
+#[derive(Debug, Clone, Default)]
+pub struct TreeLadder<P = Price> {
+    levels: BTreeMap<P, PriceLevel>,
+}
+
+impl<P: PriceValue> TreeLadder<P> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<P: PriceValue> PriceLadder<P> for TreeLadder<P> {
+    fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    fn get(&self, price: P) -> Option<&PriceLevel> {
+        self.levels.get(&price)
+    }
+
+    fn get_mut(&mut self, price: P) -> Option<&mut PriceLevel> {
+        self.levels.get_mut(&price)
+    }
+
+    fn entry(&mut self, price: P) -> &mut PriceLevel {
+        self.levels.entry(price).or_default()
+    }
+
+    fn remove(&mut self, price: P) -> Option<PriceLevel> {
+        self.levels.remove(&price)
+    }
+
+    fn min_price(&self) -> Option<P> {
+        self.levels.keys().next().copied()
+    }
+
+    fn max_price(&self) -> Option<P> {
+        self.levels.keys().next_back().copied()
+    }
+
+    fn range(&self, min_price: P, max_price: P) -> Levels<'_, P> {
+        if min_price > max_price {
+            return Box::new(std::iter::empty());
+        }
+        Box::new(self.levels.range(min_price..=max_price).map(|(price, level): (&P, &PriceLevel)| (*price, level)))
+    }
+}
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Levels kept in a contiguous array, one slot per tick from `min_price` to
+/// `max_price`. A bitmap marks occupied slots, and a summary bitmap marks
+/// non-empty bitmap words, so the best and next levels are found without
+/// walking empty ticks.
+///
+/// Prices off the tick grid or outside the range, such as auction market
+/// orders waiting at the extreme price, go to an overflow map instead, so
+/// every price is still accepted. The array holds one slot per tick, so the
+/// range should be kept to the instrument's price band.
+#[derive(Debug, Clone)]
+pub struct ArrayLadder<P = Price> {
+    min_price: P,
+    tick_size: P,
+    levels: Vec<PriceLevel>,
+    occupied: Vec<u64>,
+    summary: Vec<u64>,
+    occupied_count: usize,
+    overflow: BTreeMap<P, PriceLevel>,
+}
+
+impl<P: PriceValue> ArrayLadder<P> {
+    /// Most array slots a ladder allocates
+    pub const MAX_SLOTS: usize = 1 << 20;
+
+    /// Ladder with a slot for every `tick_size` step from `min_price` to
+    /// `max_price`. A tick size below 1 is treated as 1. An empty range, or
+    /// one needing more than `MAX_SLOTS` slots, keeps every level in the
+    /// overflow map.
+    pub fn new(min_price: P, max_price: P, tick_size: P) -> Self {
+        let tick_size: P = tick_size.max(P::ONE);
+        let slots: usize = if min_price <= max_price {
+            usize::try_from((max_price.to_i128() - min_price.to_i128()) / tick_size.to_i128() + 1)
+                .ok()
+                .filter(|slots: &usize| *slots <= Self::MAX_SLOTS)
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        let words: usize = slots.div_ceil(WORD_BITS);
+        Self {
+            min_price,
+            tick_size,
+            levels: vec![PriceLevel::new(); slots],
+            occupied: vec![0; words],
+            summary: vec![0; words.div_ceil(WORD_BITS)],
+            occupied_count: 0,
+            overflow: BTreeMap::new(),
+        }
+    }
+
+    /// Array slot holding `price`, if it is on the grid and in range
+    fn slot(&self, price: P) -> Option<usize> {
+        let offset: i128 = price.to_i128() - self.min_price.to_i128();
+        let tick_size: i128 = self.tick_size.to_i128();
+        if offset < 0 || offset % tick_size != 0 {
+            return None;
+        }
+        let slot: usize = usize::try_from(offset / tick_size).ok()?;
+        (slot < self.levels.len()).then_some(slot)
+    }
+
+    fn slot_price(&self, slot: usize) -> P {
+        P::saturating_from_i128(self.min_price.to_i128() + slot as i128 * self.tick_size.to_i128())
+    }
+
+    /// First slot priced at or above `price`
+    fn slot_at_or_above(&self, price: P) -> usize {
+        let offset: i128 = price.to_i128() - self.min_price.to_i128();
+        if offset <= 0 {
+            return 0;
+        }
+        let tick_size: i128 = self.tick_size.to_i128();
+        let slot: i128 = (offset + tick_size - 1) / tick_size;
+        slot.min(self.levels.len() as i128) as usize
+    }
+
+    /// First slot priced above `price`
+    fn slot_above(&self, price: P) -> usize {
+        let offset: i128 = price.to_i128() - self.min_price.to_i128();
+        if offset < 0 {
+            return 0;
+        }
+        let slot: i128 = offset / self.tick_size.to_i128() + 1;
+        slot.min(self.levels.len() as i128) as usize
+    }
+
+    fn is_occupied(&self, slot: usize) -> bool {
+        self.occupied[slot / WORD_BITS] & (1 << (slot % WORD_BITS)) != 0
+    }
+
+    fn set_occupied(&mut self, slot: usize) {
+        let word: usize = slot / WORD_BITS;
+        self.occupied[word] |= 1 << (slot % WORD_BITS);
+        self.summary[word / WORD_BITS] |= 1 << (word % WORD_BITS);
+    }
+
+    fn clear_occupied(&mut self, slot: usize) {
+        let word: usize = slot / WORD_BITS;
+        self.occupied[word] &= !(1 << (slot % WORD_BITS));
+        if self.occupied[word] == 0 {
+            self.summary[word / WORD_BITS] &= !(1 << (word % WORD_BITS));
+        }
+    }
+
+    /// First occupied slot in `from..to`
+    fn next_occupied(&self, from: usize, to: usize) -> Option<usize> {
+        if from >= to {
+            return None;
+        }
+        let word: usize = from / WORD_BITS;
+        let bits: u64 = self.occupied[word] & (u64::MAX << (from % WORD_BITS));
+        let slot: usize = if bits != 0 {
+            word * WORD_BITS + bits.trailing_zeros() as usize
+        } else {
+            let word: usize = next_set_bit(&self.summary, word + 1)?;
+            word * WORD_BITS + self.occupied[word].trailing_zeros() as usize
+        };
+        (slot < to).then_some(slot)
+    }
+
+    /// Last occupied slot in `from..to`
+    fn prev_occupied(&self, from: usize, to: usize) -> Option<usize> {
+        if from >= to {
+            return None;
+        }
+        let last: usize = to - 1;
+        let word: usize = last / WORD_BITS;
+        let bits: u64 = self.occupied[word] & (u64::MAX >> (WORD_BITS - 1 - last % WORD_BITS));
+        let slot: usize = if bits != 0 {
+            word * WORD_BITS + (WORD_BITS - 1 - bits.leading_zeros() as usize)
+        } else {
+            let word: usize = prev_set_bit(&self.summary, word.checked_sub(1)?)?;
+            word * WORD_BITS + (WORD_BITS - 1 - self.occupied[word].leading_zeros() as usize)
+        };
+        (slot >= from).then_some(slot)
+    }
+}
+
+/// Index of the first set bit at or after `from`
+fn next_set_bit(words: &[u64], from: usize) -> Option<usize> {
+    let mut word: usize = from / WORD_BITS;
+    let mut bits: u64 = words.get(word)? & (u64::MAX << (from % WORD_BITS));
+    loop {
+        if bits != 0 {
+            return Some(word * WORD_BITS + bits.trailing_zeros() as usize);
+        }
+        word += 1;
+        bits = *words.get(word)?;
+    }
+}
+
+/// Index of the last set bit at or before `to`
+fn prev_set_bit(words: &[u64], to: usize) -> Option<usize> {
+    let mut word: usize = to / WORD_BITS;
+    let mut bits: u64 = words.get(word)? & (u64::MAX >> (WORD_BITS - 1 - to % WORD_BITS));
+    loop {
+        if bits != 0 {
+            return Some(word * WORD_BITS + (WORD_BITS - 1 - bits.leading_zeros() as usize));
+        }
+        word = word.checked_sub(1)?;
+        bits = words[word];
+    }
+}
+
+impl<P: PriceValue> PriceLadder<P> for ArrayLadder<P> {
+    fn len(&self) -> usize {
+        self.occupied_count + self.overflow.len()
+    }
+
+    fn get(&self, price: P) -> Option<&PriceLevel> {
+        match self.slot(price) {
+            Some(slot) => self.is_occupied(slot).then(|| &self.levels[slot]),
+            None => self.overflow.get(&price),
+        }
+    }
+
+    fn get_mut(&mut self, price: P) -> Option<&mut PriceLevel> {
+        match self.slot(price) {
+            Some(slot) => self.is_occupied(slot).then(|| &mut self.levels[slot]),
+            None => self.overflow.get_mut(&price),
+        }
+    }
+
+    fn entry(&mut self, price: P) -> &mut PriceLevel {
+        let Some(slot) = self.slot(price) else {
+            return self.overflow.entry(price).or_default();
+        };
+        if !self.is_occupied(slot) {
+            self.set_occupied(slot);
+            self.occupied_count += 1;
+            self.levels[slot] = PriceLevel::new();
+        }
+        &mut self.levels[slot]
+    }
+
+    fn remove(&mut self, price: P) -> Option<PriceLevel> {
+        let Some(slot) = self.slot(price) else {
+            return self.overflow.remove(&price);
+        };
+        if !self.is_occupied(slot) {
+            return None;
+        }
+        self.clear_occupied(slot);
+        self.occupied_count -= 1;
+        Some(std::mem::take(&mut self.levels[slot]))
+    }
+
+    fn min_price(&self) -> Option<P> {
+        let array: Option<P> = self.next_occupied(0, self.levels.len()).map(|slot: usize| self.slot_price(slot));
+        let overflow: Option<P> = self.overflow.keys().next().copied();
+        match (array, overflow) {
+            (Some(array), Some(overflow)) => Some(array.min(overflow)),
+            (array, overflow) => array.or(overflow),
+        }
+    }
+
+    fn max_price(&self) -> Option<P> {
+        let array: Option<P> = self.prev_occupied(0, self.levels.len()).map(|slot: usize| self.slot_price(slot));
+        let overflow: Option<P> = self.overflow.keys().next_back().copied();
+        array.max(overflow)
+    }
+
+    fn range(&self, min_price: P, max_price: P) -> Levels<'_, P> {
+        if min_price > max_price {
+            return Box::new(std::iter::empty());
+        }
+        Box::new(ArrayLevels {
+            ladder: self,
+            front: self.slot_at_or_above(min_price),
+            back: self.slot_above(max_price),
+            overflow: self.overflow.range(min_price..=max_price),
+            overflow_front: None,
+            overflow_back: None,
+        })
+    }
+}
+
+/// Levels of an `ArrayLadder`, merging array slots with the overflow map in
+/// price order
+struct ArrayLevels<'a, P> {
+    ladder: &'a ArrayLadder<P>,
+    /// Array slots still to visit are `front..back`
+    front: usize,
+    back: usize,
+    overflow: btree_map::Range<'a, P, PriceLevel>,
+    overflow_front: Option<(P, &'a PriceLevel)>,
+    overflow_back: Option<(P, &'a PriceLevel)>,
+}
+
+impl<'a, P: PriceValue> ArrayLevels<'a, P> {
+    fn peek_overflow_front(&mut self) -> Option<P> {
+        if self.overflow_front.is_none() {
+            self.overflow_front = self.overflow.next()
+                .map(|(price, level): (&P, &'a PriceLevel)| (*price, level))
+                .or_else(|| self.overflow_back.take());
+        }
+        self.overflow_front.map(|(price, _)| price)
+    }
+
+    fn peek_overflow_back(&mut self) -> Option<P> {
+        if self.overflow_back.is_none() {
+            self.overflow_back = self.overflow.next_back()
+                .map(|(price, level): (&P, &'a PriceLevel)| (*price, level))
+                .or_else(|| self.overflow_front.take());
+        }
+        self.overflow_back.map(|(price, _)| price)
+    }
+}
+
+impl<'a, P: PriceValue> Iterator for ArrayLevels<'a, P> {
+    type Item = (P, &'a PriceLevel);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slot: Option<usize> = self.ladder.next_occupied(self.front, self.back);
+        let overflow_price: Option<P> = self.peek_overflow_front();
+        match slot {
+            Some(slot) if overflow_price.is_none_or(|price: P| self.ladder.slot_price(slot) < price) => {
+                self.front = slot + 1;
+                Some((self.ladder.slot_price(slot), &self.ladder.levels[slot]))
+            }
+            _ => self.overflow_front.take(),
+        }
+    }
+}
+
+impl<P: PriceValue> DoubleEndedIterator for ArrayLevels<'_, P> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let slot: Option<usize> = self.ladder.prev_occupied(self.front, self.back);
+        let overflow_price: Option<P> = self.peek_overflow_back();
+        match slot {
+            Some(slot) if overflow_price.is_none_or(|price: P| self.ladder.slot_price(slot) > price) => {
+                self.back = slot;
+                Some((self.ladder.slot_price(slot), &self.ladder.levels[slot]))
+            }
+            _ => self.overflow_back.take(),
+        }
+    }
+}
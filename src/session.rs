@@ -0,0 +1,47 @@
+//! Trading session phases.
+
+/// Phase of the trading session, deciding what the book accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TradingPhase {
+    /// Orders and cancels are accepted and accumulate in a call auction
+    /// without matching
+    PreOpen,
+    /// Continuous trading
+    #[default]
+    Open,
+    /// Trading is paused: only cancels are accepted
+    Halted,
+    /// Nothing is accepted
+    Closed,
+}
+
+impl TradingPhase {
+    /// Whether new orders and modifications are accepted
+    pub fn accepts_orders(&self) -> bool {
+        matches!(self, TradingPhase::PreOpen | TradingPhase::Open)
+    }
+
+    /// Whether cancels are accepted
+    pub fn accepts_cancels(&self) -> bool {
+        !matches!(self, TradingPhase::Closed)
+    }
+
+    /// Whether the session may move from this phase to `next`.
+    ///
+    /// A session runs Closed, PreOpen, Open and back to Closed. An open
+    /// market can be halted, and a halt ends by reopening directly, through
+    /// a pre-open auction, or by closing.
+    pub fn can_transition_to(&self, next: TradingPhase) -> bool {
+        matches!(
+            (self, next),
+            (TradingPhase::Closed, TradingPhase::PreOpen)
+                | (TradingPhase::PreOpen, TradingPhase::Open)
+                | (TradingPhase::PreOpen, TradingPhase::Closed)
+                | (TradingPhase::Open, TradingPhase::Halted)
+                | (TradingPhase::Open, TradingPhase::Closed)
+                | (TradingPhase::Halted, TradingPhase::Open)
+                | (TradingPhase::Halted, TradingPhase::PreOpen)
+                | (TradingPhase::Halted, TradingPhase::Closed)
+        )
+    }
+}
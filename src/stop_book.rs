@@ -0,0 +1,151 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use crate::{
+    numeric::{PriceValue, QuantityValue},
+    order::{Order, Orders},
+    types::{OrderId, Price, Quantity, Side},
+};
+
+/// Untriggered Stop and StopLimit orders, keyed by stop price.
+///
+/// Buy stops trigger once the last trade price rises to or above their stop
+/// price, sell stops once it falls to or below it. Trailing stops move
+/// their stop price as the last trade price moves in their favour.
+#[derive(Debug, Default)]
+pub struct StopBook<P = Price, Q = Quantity> {
+    buy_stops: BTreeMap<P, Orders<P, Q>>,
+    sell_stops: BTreeMap<P, Orders<P, Q>>,
+    index: HashMap<OrderId, (Side, P)>,
+    trailing: BTreeSet<OrderId>,
+}
+
+impl<P: PriceValue, Q: QuantityValue> StopBook<P, Q> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a stop on `side` at `stop_price` is triggered by `last_trade_price`
+    pub fn is_triggered(side: Side, stop_price: P, last_trade_price: P) -> bool {
+        match side {
+            Side::Buy => last_trade_price >= stop_price,
+            Side::Sell => last_trade_price <= stop_price,
+        }
+    }
+
+    /// Add an order to the stop book. Orders without a stop price are ignored.
+    pub fn insert(&mut self, order: Order<P, Q>) {
+        let Some(stop_price) = order.get_stop_price() else {
+            return;
+        };
+        let side: Side = order.get_side();
+        self.index.insert(order.get_order_id(), (side, stop_price));
+        if order.get_trail().is_some() {
+            self.trailing.insert(order.get_order_id());
+        }
+        self.side_mut(side).entry(stop_price).or_default().push(order);
+    }
+
+    /// Remove an untriggered stop, returning it if it was present
+    pub fn remove(&mut self, order_id: OrderId) -> Option<Order<P, Q>> {
+        let (side, stop_price) = self.index.remove(&order_id)?;
+        self.trailing.remove(&order_id);
+        let stops: &mut BTreeMap<P, Orders<P, Q>> = self.side_mut(side);
+        let orders: &mut Orders<P, Q> = stops.get_mut(&stop_price)?;
+        let position: usize = orders.iter().position(|o: &Order<P, Q>| o.get_order_id() == order_id)?;
+        let order: Order<P, Q> = orders.remove(position);
+        if orders.is_empty() {
+            stops.remove(&stop_price);
+        }
+        Some(order)
+    }
+
+    pub fn contains(&self, order_id: OrderId) -> bool {
+        self.index.contains_key(&order_id)
+    }
+
+    pub fn get(&self, order_id: OrderId) -> Option<&Order<P, Q>> {
+        let (side, stop_price) = self.index.get(&order_id)?;
+        let stops: &BTreeMap<P, Orders<P, Q>> = match side {
+            Side::Buy => &self.buy_stops,
+            Side::Sell => &self.sell_stops,
+        };
+        stops.get(stop_price)?.iter().find(|o: &&Order<P, Q>| o.get_order_id() == order_id)
+    }
+
+    /// Untriggered stops, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &Order<P, Q>> {
+        self.buy_stops.values().chain(self.sell_stops.values()).flatten()
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Remove and return every stop triggered by `last_trade_price`.
+    ///
+    /// Stops are returned in the order the price would have crossed them:
+    /// buy stops from the lowest stop price up, sell stops from the highest
+    /// down, and first in first out within a stop price.
+    pub fn take_triggered(&mut self, last_trade_price: P) -> Orders<P, Q> {
+        self.take_triggered_in_range(last_trade_price, last_trade_price)
+    }
+
+    /// Remove and return every stop triggered by trades from `low` to `high`:
+    /// buy stops at or below `high` and sell stops at or above `low`, in the
+    /// same order as `take_triggered`
+    pub fn take_triggered_in_range(&mut self, low: P, high: P) -> Orders<P, Q> {
+        let mut triggered: Orders<P, Q> = Vec::new();
+
+        let buy_prices: Vec<P> = self.buy_stops.range(..=high).map(|(price, _)| *price).collect();
+        for price in buy_prices {
+            if let Some(orders) = self.buy_stops.remove(&price) {
+                triggered.extend(orders);
+            }
+        }
+
+        let sell_prices: Vec<P> = self.sell_stops.range(low..).rev().map(|(price, _)| *price).collect();
+        for price in sell_prices {
+            if let Some(orders) = self.sell_stops.remove(&price) {
+                triggered.extend(orders);
+            }
+        }
+
+        for order in &triggered {
+            self.index.remove(&order.get_order_id());
+            self.trailing.remove(&order.get_order_id());
+        }
+
+        triggered
+    }
+
+    /// Move trailing stops after a trade at `last_trade_price`. Sell stops
+    /// only ever rise and buy stops only ever fall; a moved stop joins the
+    /// back of the queue at its new stop price. Stops keep at least
+    /// `min_distance` from the trade.
+    pub fn ratchet_trailing_stops(&mut self, last_trade_price: P, min_distance: P) {
+        let order_ids: Vec<OrderId> = self.trailing.iter().copied().collect();
+
+        for order_id in order_ids {
+            let Some(stop_price) = self.get(order_id)
+                .and_then(|order: &Order<P, Q>| order.trailing_stop_price(last_trade_price, min_distance))
+            else {
+                continue;
+            };
+
+            if let Some(mut order) = self.remove(order_id) {
+                order.stop_price = Some(stop_price);
+                self.insert(order);
+            }
+        }
+    }
+
+    fn side_mut(&mut self, side: Side) -> &mut BTreeMap<P, Orders<P, Q>> {
+        match side {
+            Side::Buy => &mut self.buy_stops,
+            Side::Sell => &mut self.sell_stops,
+        }
+    }
+}
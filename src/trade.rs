@@ -1,30 +1,38 @@
-use crate::{OrderId, Price, Quantity};
+use crate::{OrderId, ParticipantId, Price, Quantity};
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct TradeInfo {
+pub struct TradeInfo<P = Price, Q = Quantity> {
     pub order_id: OrderId,
-    pub price: Price,
-    pub quantity: Quantity,
+    pub price: P,
+    pub quantity: Q,
+    pub participant: Option<ParticipantId>,
 }
 
-impl TradeInfo {
-    pub fn new(order_id: OrderId, price: Price, quantity: Quantity) -> Self {
+impl<P, Q> TradeInfo<P, Q> {
+    pub fn new(order_id: OrderId, price: P, quantity: Q) -> Self {
         Self {
             order_id,
             price,
             quantity,
+            participant: None,
         }
     }
+
+    /// Set the participant that owned the order
+    pub fn with_participant(mut self, participant: Option<ParticipantId>) -> Self {
+        self.participant = participant;
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Trade {
-    pub bid_info: TradeInfo,
-    pub ask_info: TradeInfo,
+pub struct Trade<P = Price, Q = Quantity> {
+    pub bid_info: TradeInfo<P, Q>,
+    pub ask_info: TradeInfo<P, Q>,
 }
 
-impl Trade {
-    pub fn new(bid_info: TradeInfo, ask_info: TradeInfo) -> Self {
+impl<P, Q> Trade<P, Q> {
+    pub fn new(bid_info: TradeInfo<P, Q>, ask_info: TradeInfo<P, Q>) -> Self {
         Self {
             bid_info,
             ask_info,
@@ -32,4 +40,4 @@ impl Trade {
     }
 }
 
-pub type Trades = Vec<Trade>;
+pub type Trades<P = Price, Q = Quantity> = Vec<Trade<P, Q>>;
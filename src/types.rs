@@ -1,16 +1,34 @@
 //! Core types used throughout the order book system.
 
+use crate::{
+    numeric::{PriceValue, QuantityValue},
+    order::Order,
+    trade::Trades,
+};
+
 /// Unique identifier for orders
 pub type OrderId = u64;
 
 /// Multiple order IDs
 pub type OrderIds = Vec<OrderId>;
 
-/// Price representation
-pub type Price = u32;
+/// Signed fixed-point price. The raw integer is scaled by the instrument's
+/// `PriceScale`, so with two decimals `12345` means `123.45`. This is the
+/// default price type; books can use any `PriceValue`.
+pub type Price = i64;
+
+/// Quantity of shares/units. This is the default quantity type; books can
+/// use any `QuantityValue`.
+pub type Quantity = u64;
+
+/// Participant (firm, trader or account) an order belongs to
+pub type ParticipantId = u64;
+
+/// Order id chosen by the participant, unique among that participant's orders
+pub type ClientOrderId = u64;
 
-/// Quantity of shares/units
-pub type Quantity = u32;
+/// Point in time, in nanoseconds since the Unix epoch
+pub type Timestamp = u64;
 
 /// Side of the market (buy or sell)
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -19,36 +37,102 @@ pub enum Side {
     Sell,
 }
 
-/// Type of order
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum OrderType {
-    /// Fill and Kill - execute immediately, cancel remainder
-    FillAndKill,
-    /// Fill or Kill - execute completely or cancel entirely
-    FillOrKill,
-    /// Good Till Cancel - remains active until cancelled
-    GoodTillCancel,
-    /// Good For Day - remains active until end of trading day
-    GoodForDay,
-    /// Limit order - execute at specified price or better
+impl Side {
+    /// The side an order on this side trades against
+    pub fn opposite(&self) -> Side {
+        match self {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        }
+    }
+}
+
+/// Execution style of an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrdType {
+    /// Execute at the specified price or better
     Limit,
-    /// Market order - execute immediately at best available price
+    /// Execute at any price, sweeping the opposite side up to its worst level
     Market,
-    /// Stop order - becomes market order when stop price is reached
+    /// Execute at the best opposite price only, resting any remainder as a
+    /// limit order at that price
+    MarketToLimit,
+    /// Becomes a market order when the stop price is reached
     Stop,
-    /// Stop limit order - becomes limit order when stop price is reached
+    /// Becomes a limit order when the stop price is reached
     StopLimit,
 }
 
+/// How long an order stays working
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeInForce {
+    /// Remains active until cancelled
+    GoodTillCancel,
+    /// Remains active until the end of the trading day
+    GoodForDay,
+    /// Remains active until its expiry timestamp
+    GoodTillDate,
+    /// Executes immediately and cancels any remainder
+    FillAndKill,
+    /// Executes completely and immediately or is cancelled entirely
+    FillOrKill,
+}
+
+impl TimeInForce {
+    /// Whether an order with this time in force may rest in the book
+    pub fn can_rest(&self) -> bool {
+        !matches!(self, TimeInForce::FillAndKill | TimeInForce::FillOrKill)
+    }
+}
+
+/// How a post-only order is handled when it would take liquidity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostOnly {
+    /// Reject the order
+    Reject,
+    /// Reprice the order one tick behind the opposite best price
+    Slide,
+}
+
+/// Distance a trailing stop keeps from the best price seen since entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailAmount<P = Price> {
+    /// Fixed price distance
+    Absolute(P),
+    /// Distance in basis points of the last trade price
+    BasisPoints(u32),
+}
+
+impl<P: PriceValue> TrailAmount<P> {
+    /// Price distance to trail `price` by
+    pub fn distance(&self, price: P) -> P {
+        match self {
+            TrailAmount::Absolute(amount) => *amount,
+            TrailAmount::BasisPoints(bps) => {
+                let distance: u128 = price.to_i128().unsigned_abs() * *bps as u128 / 10_000;
+                P::saturating_from_i128(distance as i128)
+            }
+        }
+    }
+
+    /// Whether the trail keeps a positive distance
+    pub fn is_positive(&self) -> bool {
+        match self {
+            TrailAmount::Absolute(amount) => *amount > P::ZERO,
+            TrailAmount::BasisPoints(bps) => *bps > 0,
+        }
+    }
+}
+
 /// Information about a price level in the order book
 #[derive(Debug, Clone, PartialEq)]
-pub struct LevelInfo {
-    pub price: Price,
-    pub quantity: Quantity,
+pub struct LevelInfo<P = Price, Q = Quantity> {
+    pub price: P,
+    pub quantity: Q,
 }
 
 /// Status of an order after being submitted to the order book
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OrderStatus {
     /// Order was accepted and placed in the book
     Accepted,
@@ -62,6 +146,54 @@ pub enum OrderStatus {
     RejectedFillOrKillPartialFill,
     /// Order was rejected - duplicate order ID
     RejectedDuplicateId,
+    /// Stop order was accepted and is waiting for its stop price to trade
+    PendingTrigger,
+    /// Order was rejected - Stop or StopLimit order without a stop price
+    RejectedMissingStopPrice,
+    /// Order was rejected - Good Till Date order without an expiry
+    RejectedMissingExpiry,
+    /// Order was rejected - Good Till Date expiry is not in the future
+    RejectedExpired,
+    /// Order was rejected - iceberg display quantity is zero
+    RejectedInvalidDisplayQuantity,
+    /// Post-only order was repriced behind the opposite best and placed in the book
+    Repriced,
+    /// Order was rejected - post-only order would have taken liquidity
+    RejectedPostOnlyWouldCross,
+    /// Order was rejected - pegged order has no reference price to track
+    RejectedNoPegReference,
+    /// Order was rejected - trailing stop with a zero trail amount
+    RejectedInvalidTrailAmount,
+    /// Order was rejected - order type, time in force and instructions do not go together
+    RejectedInvalidCombination,
+    /// Order was rejected - Fill-and-Kill or Fill-or-Kill order during an auction
+    RejectedImmediateOrderInAuction,
+    /// Order was rejected - trading is halted and only cancels are accepted
+    RejectedTradingHalted,
+    /// Order was rejected - the market is closed
+    RejectedMarketClosed,
+    /// Order was rejected - quantity of zero
+    RejectedInvalidQuantity,
+    /// Order was rejected - limit price outside the instrument's price range
+    RejectedInvalidPrice,
+    /// Order was rejected - price or stop price is not a multiple of the tick size
+    RejectedPriceNotOnTick,
+    /// Order was rejected - quantity or display quantity is not a multiple of the lot size
+    RejectedQuantityNotOnLot,
+    /// Order was rejected - quantity below the instrument minimum
+    RejectedBelowMinimumQuantity,
+    /// Order was rejected - quantity above the instrument maximum
+    RejectedAboveMaximumQuantity,
+    /// Order was rejected - resting it would overflow the total quantity at its price level
+    RejectedQuantityOverflow,
+    /// Modify rejected - no live order has that id
+    RejectedUnknownOrder,
+    /// Modify rejected - the order has already been filled
+    RejectedOrderFilled,
+    /// Modify rejected - an order cannot change side
+    RejectedSideChange,
+    /// Modify rejected - new quantity is not above the quantity already filled
+    RejectedQuantityBelowFilled,
 }
 
 impl OrderStatus {
@@ -73,20 +205,49 @@ impl OrderStatus {
             OrderStatus::RejectedFillAndKillNoMatch => "Fill-and-Kill order rejected: no matching orders available",
             OrderStatus::RejectedFillOrKillPartialFill => "Fill-or-Kill order rejected: insufficient liquidity for complete fill",
             OrderStatus::RejectedDuplicateId => "Order rejected: duplicate order ID",
+            OrderStatus::PendingTrigger => "Stop order accepted and waiting for stop price",
+            OrderStatus::RejectedMissingStopPrice => "Stop order rejected: no stop price specified",
+            OrderStatus::RejectedMissingExpiry => "Good-Till-Date order rejected: no expiry specified",
+            OrderStatus::RejectedExpired => "Good-Till-Date order rejected: expiry has already passed",
+            OrderStatus::RejectedInvalidDisplayQuantity => "Iceberg order rejected: display quantity must be greater than zero",
+            OrderStatus::Repriced => "Post-only order repriced to avoid crossing and placed in order book",
+            OrderStatus::RejectedPostOnlyWouldCross => "Post-only order rejected: order would take liquidity",
+            OrderStatus::RejectedNoPegReference => "Pegged order rejected: no reference price available",
+            OrderStatus::RejectedInvalidTrailAmount => "Trailing stop rejected: trail amount must be greater than zero",
+            OrderStatus::RejectedInvalidCombination => "Order rejected: order type, time in force and instructions are incompatible",
+            OrderStatus::RejectedImmediateOrderInAuction => "Order rejected: immediate orders are not accepted during an auction",
+            OrderStatus::RejectedTradingHalted => "Order rejected: trading is halted, only cancels are accepted",
+            OrderStatus::RejectedMarketClosed => "Order rejected: market is closed",
+            OrderStatus::RejectedInvalidQuantity => "Order rejected: quantity must be greater than zero",
+            OrderStatus::RejectedInvalidPrice => "Order rejected: limit price is outside the instrument's price range",
+            OrderStatus::RejectedPriceNotOnTick => "Order rejected: price is not a multiple of the tick size",
+            OrderStatus::RejectedQuantityNotOnLot => "Order rejected: quantity is not a multiple of the lot size",
+            OrderStatus::RejectedBelowMinimumQuantity => "Order rejected: quantity is below the minimum order size",
+            OrderStatus::RejectedAboveMaximumQuantity => "Order rejected: quantity is above the maximum order size",
+            OrderStatus::RejectedQuantityOverflow => "Order rejected: total quantity at the price level would overflow",
+            OrderStatus::RejectedUnknownOrder => "Modify rejected: no live order with that ID",
+            OrderStatus::RejectedOrderFilled => "Modify rejected: order is already filled",
+            OrderStatus::RejectedSideChange => "Modify rejected: order side cannot be changed",
+            OrderStatus::RejectedQuantityBelowFilled => "Modify rejected: quantity must be above the quantity already filled",
         }
     }
+
+    /// Whether the order or modification was turned away
+    pub fn is_rejected(&self) -> bool {
+        !matches!(self, OrderStatus::Accepted | OrderStatus::Executed | OrderStatus::PendingTrigger | OrderStatus::Repriced)
+    }
 }
 
 /// Result of submitting an order to the order book
 #[derive(Debug, Clone, PartialEq)]
-pub struct OrderResult {
+pub struct OrderResult<P = Price, Q = Quantity> {
     pub order_id: OrderId,
     pub status: OrderStatus,
-    pub trades: crate::trade::Trades,
+    pub trades: Trades<P, Q>,
 }
 
-impl OrderResult {
-    pub fn new(order_id: OrderId, status: OrderStatus, trades: crate::trade::Trades) -> Self {
+impl<P, Q> OrderResult<P, Q> {
+    pub fn new(order_id: OrderId, status: OrderStatus, trades: Trades<P, Q>) -> Self {
         Self {
             order_id,
             status,
@@ -94,3 +255,49 @@ impl OrderResult {
         }
     }
 }
+
+/// Why a cancel request was turned away
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelRejectReason {
+    /// No live order has that id
+    UnknownOrder,
+    /// The order has already been filled
+    AlreadyFilled,
+    /// The order belongs to another participant
+    WrongOwner,
+    /// The market is closed and accepts no cancels
+    MarketClosed,
+}
+
+impl CancelRejectReason {
+    pub fn message(&self) -> &'static str {
+        match self {
+            CancelRejectReason::UnknownOrder => "Cancel rejected: no live order with that ID",
+            CancelRejectReason::AlreadyFilled => "Cancel rejected: order is already filled",
+            CancelRejectReason::WrongOwner => "Cancel rejected: order belongs to another participant",
+            CancelRejectReason::MarketClosed => "Cancel rejected: market is closed",
+        }
+    }
+}
+
+/// An order removed by a cancel request
+#[derive(Debug, Clone, PartialEq)]
+pub struct CancelledOrder<P = Price, Q = Quantity> {
+    /// The order as it was when cancelled
+    pub order: Order<P, Q>,
+    pub remaining_quantity: Q,
+    pub filled_quantity: Q,
+}
+
+impl<P: PriceValue, Q: QuantityValue> CancelledOrder<P, Q> {
+    pub fn new(order: Order<P, Q>) -> Self {
+        Self {
+            remaining_quantity: order.get_remaining_quantity(),
+            filled_quantity: order.filled_quantity,
+            order,
+        }
+    }
+}
+
+/// Result of a cancel request: the cancelled order, or why it was rejected
+pub type CancelResult<P = Price, Q = Quantity> = Result<CancelledOrder<P, Q>, CancelRejectReason>;
@@ -0,0 +1,78 @@
+use my_order_book::{AuctionUncross, OrdType, Order, Price, Quantity, Side, TimeInForce};
+use my_order_book::auction::{equilibrium, AuctionLevel};
+
+fn levels(side: Side, orders: &[(Price, Quantity)]) -> Vec<AuctionLevel> {
+    orders.iter()
+        .enumerate()
+        .map(|(id, (price, quantity))| {
+            let order: Order = Order::new(id as u64, side, OrdType::Limit, TimeInForce::GoodTillCancel, *price, *quantity);
+            AuctionLevel::from_orders(*price, [&order].into_iter())
+        })
+        .collect()
+}
+
+#[test]
+fn test_equilibrium_maximises_volume() {
+    let bids = levels(Side::Buy, &[(102, 10), (101, 10), (100, 10)]);
+    let asks = levels(Side::Sell, &[(99, 15), (101, 10), (103, 10)]);
+
+    assert_eq!(equilibrium::<Price, Quantity>(&bids, &asks, None), Some(AuctionUncross {
+        price: 101,
+        matched_quantity: 20,
+        imbalance_quantity: 5,
+        imbalance_side: Some(Side::Sell),
+    }));
+}
+
+#[test]
+fn test_equilibrium_minimises_imbalance() {
+    let bids = levels(Side::Buy, &[(101, 10), (100, 4)]);
+    let asks = levels(Side::Sell, &[(100, 10)]);
+
+    let uncross = equilibrium::<Price, Quantity>(&bids, &asks, None).unwrap();
+    assert_eq!(uncross.price, 101);
+    assert_eq!(uncross.matched_quantity, 10);
+    assert_eq!(uncross.imbalance_side, None);
+}
+
+#[test]
+fn test_equilibrium_reference_price_tie_break() {
+    let bids = levels(Side::Buy, &[(103, 10)]);
+    let asks = levels(Side::Sell, &[(100, 10)]);
+
+    assert_eq!(equilibrium::<Price, Quantity>(&bids, &asks, None).unwrap().price, 100);
+    assert_eq!(equilibrium::<Price, Quantity>(&bids, &asks, Some(110)).unwrap().price, 103);
+    // A reference inside the crossed range is a candidate itself
+    assert_eq!(equilibrium::<Price, Quantity>(&bids, &asks, Some(102)).unwrap().price, 102);
+}
+
+#[test]
+fn test_equilibrium_without_cross() {
+    let bids = levels(Side::Buy, &[(99, 10)]);
+    let asks = levels(Side::Sell, &[(100, 10)]);
+
+    assert_eq!(equilibrium::<Price, Quantity>(&bids, &asks, None), None);
+    assert_eq!(equilibrium::<Price, Quantity>(&[], &asks, Some(100)), None);
+}
+
+#[test]
+fn test_equilibrium_ignores_market_order_prices() {
+    let market: Order = Order::new(0, Side::Buy, OrdType::Market, TimeInForce::GoodTillCancel, Price::MAX, 5);
+    let bids = vec![AuctionLevel::from_orders(Price::MAX, [&market].into_iter())];
+    let asks = levels(Side::Sell, &[(100, 5), (102, 5)]);
+
+    let uncross = equilibrium::<Price, Quantity>(&bids, &asks, None).unwrap();
+    assert_eq!(uncross.price, 100);
+    assert_eq!(uncross.matched_quantity, 5);
+}
+
+#[test]
+fn test_auction_level_from_orders() {
+    let limit: Order = Order::new(0, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let market: Order = Order::new(1, Side::Buy, OrdType::Market, TimeInForce::GoodTillCancel, 100, 5);
+
+    let level: AuctionLevel = AuctionLevel::from_orders(100, [&limit, &market].into_iter());
+    assert_eq!(level.quantity, 15);
+    assert!(level.has_limit_orders);
+    assert!(!AuctionLevel::from_orders(100, [&market].into_iter()).has_limit_orders);
+}
@@ -0,0 +1,26 @@
+use my_order_book::{BreachAction, Price, PriceBand};
+
+#[test]
+fn test_price_band_creation() {
+    let band: PriceBand = PriceBand::new(500, BreachAction::Halt);
+    assert_eq!(band.basis_points, 500);
+    assert_eq!(band.action, BreachAction::Halt);
+    assert_eq!(band.reference_price, None);
+
+    let band: PriceBand = PriceBand::new(500, BreachAction::VolatilityAuction).with_reference_price(200);
+    assert_eq!(band.reference_price, Some(200));
+}
+
+#[test]
+fn test_price_band_limits() {
+    let band: PriceBand = PriceBand::new(500, BreachAction::Halt);
+    assert_eq!(band.limits(100), (95, 105));
+    assert_eq!(band.limits(1_000), (950, 1_050));
+    assert_eq!(PriceBand::<Price>::new(20_000, BreachAction::Halt).limits(100), (-100, 300));
+    assert_eq!(band.limits(-200), (-210, -190));
+
+    assert!(band.contains(100, 95));
+    assert!(band.contains(100, 105));
+    assert!(!band.contains(100, 94));
+    assert!(!band.contains(100, 106));
+}
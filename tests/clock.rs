@@ -0,0 +1,55 @@
+use my_order_book::{Clock, ManualClock, MarketSchedule, SystemClock};
+use my_order_book::clock::{NANOS_PER_DAY, NANOS_PER_SECOND};
+
+const HOUR: u64 = 60 * 60 * NANOS_PER_SECOND;
+
+#[test]
+fn test_manual_clock_set_and_advance() {
+    let clock = ManualClock::new(1_000);
+    assert_eq!(clock.now(), 1_000);
+
+    clock.advance(500);
+    assert_eq!(clock.now(), 1_500);
+
+    clock.set(42);
+    assert_eq!(clock.now(), 42);
+}
+
+#[test]
+fn test_manual_clock_clones_share_time() {
+    let clock = ManualClock::new(0);
+    let handle = clock.clone();
+
+    handle.advance(HOUR);
+    assert_eq!(clock.now(), HOUR);
+}
+
+#[test]
+fn test_system_clock_is_after_epoch() {
+    assert!(SystemClock.now() > 0);
+}
+
+#[test]
+fn test_market_schedule_daily_close() {
+    let schedule = MarketSchedule::daily_close(16, 30);
+    assert_eq!(schedule.close_time_of_day, 16 * HOUR + 30 * 60 * NANOS_PER_SECOND);
+}
+
+#[test]
+fn test_market_schedule_wraps_time_of_day() {
+    let schedule = MarketSchedule::new(NANOS_PER_DAY + HOUR);
+    assert_eq!(schedule.close_time_of_day, HOUR);
+}
+
+#[test]
+fn test_next_close_after() {
+    let schedule = MarketSchedule::daily_close(16, 0);
+    let day = 3 * NANOS_PER_DAY;
+
+    // Before close: today's close
+    assert_eq!(schedule.next_close_after(day + 9 * HOUR), day + 16 * HOUR);
+
+    // Exactly at close and after close: tomorrow's close
+    assert_eq!(schedule.next_close_after(day + 16 * HOUR), day + NANOS_PER_DAY + 16 * HOUR);
+    assert_eq!(schedule.next_close_after(day + 20 * HOUR), day + NANOS_PER_DAY + 16 * HOUR);
+}
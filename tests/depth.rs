@@ -0,0 +1,66 @@
+use my_order_book::{Depth, DepthLevel, Price, Quantity, Side};
+
+fn prices(depth: &Depth) -> Vec<Price> {
+    depth.iter().map(|(price, _): (Price, &DepthLevel)| price).collect()
+}
+
+#[test]
+fn test_levels_are_kept_best_first() {
+    let mut bids: Depth = Depth::new(Side::Buy);
+    let mut asks: Depth = Depth::new(Side::Sell);
+    for price in [99, 101, 100] {
+        bids.add_order(price, 10);
+        asks.add_order(price + 10, 10);
+    }
+
+    assert_eq!(prices(&bids), vec![101, 100, 99]);
+    assert_eq!(prices(&asks), vec![109, 110, 111]);
+}
+
+#[test]
+fn test_level_count_and_quantity() {
+    let mut depth: Depth = Depth::new(Side::Sell);
+    depth.add_order(100, 10);
+    depth.add_order(100, 5);
+    depth.reduce(100, 3);
+    assert_eq!(depth.get_level(100), Some(&DepthLevel { quantity: 12, count: 2 }));
+
+    depth.remove_order(100, 7);
+    assert_eq!(depth.get_level(100), Some(&DepthLevel { quantity: 5, count: 1 }));
+    depth.remove_order(100, 5);
+    assert!(depth.get_level(100).is_none());
+    assert!(depth.is_empty());
+}
+
+#[test]
+fn test_liquidity_up_to_limit_price() {
+    let mut asks: Depth = Depth::new(Side::Sell);
+    asks.add_order(100, 10);
+    asks.add_order(101, 20);
+    asks.add_order(103, 40);
+    let mut bids: Depth = Depth::new(Side::Buy);
+    bids.add_order(99, 10);
+    bids.add_order(97, 20);
+
+    assert_eq!(asks.get_liquidity_up_to(99), 0);
+    assert_eq!(asks.get_liquidity_up_to(102), 30);
+    assert_eq!(bids.get_liquidity_up_to(98), 10);
+    assert_eq!(bids.get_liquidity_up_to(90), 30);
+
+    assert!(asks.can_fill(101, 30));
+    assert!(!asks.can_fill(101, 31));
+    assert!(asks.can_fill(103, 70));
+}
+
+#[test]
+fn test_liquidity_saturates_and_room_checks_overflow() {
+    let mut asks: Depth = Depth::new(Side::Sell);
+    asks.add_order(100, Quantity::MAX);
+    asks.add_order(101, 10);
+
+    assert_eq!(asks.get_liquidity_up_to(101), Quantity::MAX);
+    assert!(asks.can_fill(101, Quantity::MAX));
+    assert!(!asks.has_room(100, 1));
+    assert!(asks.has_room(101, 1));
+    assert!(asks.has_room(102, Quantity::MAX));
+}
@@ -0,0 +1,121 @@
+use my_order_book::{InstrumentRules, OrdType, Order, OrderRequest, OrderStatus, Price, Quantity, PriceScale, Peg, PegType, Side, TickTable, TimeInForce};
+
+fn limit(price: Price, quantity: Quantity) -> Order {
+    Order::new(1, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, price, quantity)
+}
+
+#[test]
+fn test_fixed_tick_table() {
+    let ticks: TickTable = TickTable::fixed(5);
+    assert_eq!(ticks.tick_size_at(0), 5);
+    assert_eq!(ticks.tick_size_at(1_000), 5);
+    assert!(ticks.is_on_tick(105));
+    assert!(!ticks.is_on_tick(103));
+    assert_eq!(ticks.round_down(103), 100);
+    assert_eq!(ticks.round_up(103), Some(105));
+    assert_eq!(ticks.round_up(105), Some(105));
+    assert_eq!(ticks.tick_below(105), Some(100));
+    assert_eq!(ticks.tick_above(105), Some(110));
+    assert_eq!(ticks.tick_below(0), Some(-5));
+    assert_eq!(ticks.round_down(-3), -5);
+    assert_eq!(ticks.tick_below(Price::MIN), None);
+}
+
+#[test]
+fn test_price_dependent_tick_table() {
+    let ticks: TickTable = TickTable::new(vec![(1_000, 5), (100, 2)]);
+    assert_eq!(ticks.tick_size_at(99), 1);
+    assert_eq!(ticks.tick_size_at(100), 2);
+    assert_eq!(ticks.tick_size_at(999), 2);
+    assert_eq!(ticks.tick_size_at(1_000), 5);
+
+    assert!(ticks.is_on_tick(99));
+    assert!(!ticks.is_on_tick(101));
+    assert!(!ticks.is_on_tick(1_002));
+
+    // Stepping across a band boundary uses the tick on the far side
+    assert_eq!(ticks.tick_below(1_000), Some(998));
+    assert_eq!(ticks.tick_above(998), Some(1_000));
+    assert_eq!(ticks.tick_above(1_000), Some(1_005));
+}
+
+#[test]
+fn test_default_rules() {
+    let rules = InstrumentRules::default();
+    assert_eq!(rules.validate(&limit(100, 1)), None);
+    assert_eq!(rules.validate(&limit(100, 0)), Some(OrderStatus::RejectedInvalidQuantity));
+    assert_eq!(rules.validate(&limit(0, 10)), Some(OrderStatus::RejectedInvalidPrice));
+
+    // Orders without a limit price of their own are not price checked
+    let market: Order = Order::new(1, Side::Buy, OrdType::Market, TimeInForce::GoodTillCancel, 0, 10);
+    assert_eq!(rules.validate(&market), None);
+    let pegged: Order = OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 0, 10)
+        .with_peg(Peg::new(PegType::Primary))
+        .to_order(1);
+    assert_eq!(rules.validate(&pegged), None);
+}
+
+#[test]
+fn test_tick_and_lot_validation() {
+    let rules: InstrumentRules = InstrumentRules::new().with_tick_size(5).with_lot_size(10);
+
+    assert_eq!(rules.validate(&limit(105, 20)), None);
+    assert_eq!(rules.validate(&limit(103, 20)), Some(OrderStatus::RejectedPriceNotOnTick));
+    assert_eq!(rules.validate(&limit(105, 25)), Some(OrderStatus::RejectedQuantityNotOnLot));
+
+    let stop: Order = OrderRequest::new(Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 10)
+        .with_stop_price(107)
+        .to_order(1);
+    assert_eq!(rules.validate(&stop), Some(OrderStatus::RejectedPriceNotOnTick));
+
+    let iceberg: Order = OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 100)
+        .with_display_quantity(15)
+        .to_order(1);
+    assert_eq!(rules.validate(&iceberg), Some(OrderStatus::RejectedQuantityNotOnLot));
+}
+
+#[test]
+fn test_quantity_limits() {
+    let rules: InstrumentRules = InstrumentRules::new().with_min_quantity(10).with_max_quantity(1_000);
+
+    assert_eq!(rules.validate(&limit(100, 10)), None);
+    assert_eq!(rules.validate(&limit(100, 1_000)), None);
+    assert_eq!(rules.validate(&limit(100, 9)), Some(OrderStatus::RejectedBelowMinimumQuantity));
+    assert_eq!(rules.validate(&limit(100, 1_001)), Some(OrderStatus::RejectedAboveMaximumQuantity));
+}
+
+#[test]
+fn test_price_range() {
+    let rules: InstrumentRules = InstrumentRules::new();
+    assert_eq!(rules.validate(&limit(1, 10)), None);
+    assert_eq!(rules.validate(&limit(0, 10)), Some(OrderStatus::RejectedInvalidPrice));
+    assert_eq!(rules.validate(&limit(-5, 10)), Some(OrderStatus::RejectedInvalidPrice));
+
+    let rules: InstrumentRules = InstrumentRules::new().with_price_range(-1_000, 1_000).with_tick_size(5);
+    assert!(rules.is_price_in_range(-1_000));
+    assert!(!rules.is_price_in_range(1_001));
+    assert_eq!(rules.validate(&limit(-5, 10)), None);
+    assert_eq!(rules.validate(&limit(0, 10)), None);
+    assert_eq!(rules.validate(&limit(-3, 10)), Some(OrderStatus::RejectedPriceNotOnTick));
+    assert_eq!(rules.validate(&limit(-1_005, 10)), Some(OrderStatus::RejectedInvalidPrice));
+}
+
+#[test]
+fn test_stop_price_range() {
+    let rules: InstrumentRules = InstrumentRules::new().with_price_range(50, 150);
+    let stop = |stop_price: Price| OrderRequest::new(Side::Sell, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 10)
+        .with_stop_price(stop_price)
+        .to_order(1);
+
+    assert_eq!(rules.validate(&stop(50)), None);
+    assert_eq!(rules.validate(&stop(49)), Some(OrderStatus::RejectedInvalidPrice));
+    assert_eq!(rules.validate(&stop(151)), Some(OrderStatus::RejectedInvalidPrice));
+}
+
+#[test]
+fn test_instrument_price_scale() {
+    let rules: InstrumentRules = InstrumentRules::new().with_price_scale(PriceScale::new(2));
+    assert_eq!(rules.parse_price("-3.25"), Some(-325));
+    assert_eq!(rules.parse_price("3.255"), None);
+    assert_eq!(rules.format_price(1_050), "10.50");
+}
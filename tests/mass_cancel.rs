@@ -0,0 +1,31 @@
+use my_order_book::{MassCancel, Order, OrdType, Price, Quantity, Side, TimeInForce};
+
+fn order(side: Side, ord_type: OrdType, price: Price, participant: Option<u64>) -> Order {
+    let mut order: Order = Order::<Price, Quantity>::new(1, side, ord_type, TimeInForce::GoodTillCancel, price, 10);
+    order.participant = participant;
+    order
+}
+
+#[test]
+fn test_empty_filter_matches_everything() {
+    let filter: MassCancel = MassCancel::new();
+    assert!(filter.matches(&order(Side::Buy, OrdType::Limit, 100, None)));
+    assert!(filter.matches(&order(Side::Sell, OrdType::StopLimit, -5, Some(3))));
+}
+
+#[test]
+fn test_filters_combine() {
+    let filter: MassCancel = MassCancel::new()
+        .with_participant(1)
+        .with_side(Side::Buy)
+        .with_price_range(95, 100)
+        .with_ord_type(OrdType::Limit);
+
+    assert!(filter.matches(&order(Side::Buy, OrdType::Limit, 95, Some(1))));
+    assert!(filter.matches(&order(Side::Buy, OrdType::Limit, 100, Some(1))));
+    assert!(!filter.matches(&order(Side::Buy, OrdType::Limit, 101, Some(1))));
+    assert!(!filter.matches(&order(Side::Sell, OrdType::Limit, 100, Some(1))));
+    assert!(!filter.matches(&order(Side::Buy, OrdType::Limit, 100, Some(2))));
+    assert!(!filter.matches(&order(Side::Buy, OrdType::Limit, 100, None)));
+    assert!(!filter.matches(&order(Side::Buy, OrdType::StopLimit, 100, Some(1))));
+}
@@ -0,0 +1,69 @@
+use my_order_book::{Fifo, LeadMarketMaker, MatchingPolicy, OrdType, Order, OrderRequest, ProRata, ProRataTopOrder, Quantity, Side, TimeInForce};
+
+fn level(quantities: &[Quantity]) -> Vec<Order> {
+    quantities.iter()
+        .enumerate()
+        .map(|(id, quantity)| Order::new(id as u64, Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, *quantity))
+        .collect()
+}
+
+#[test]
+fn test_fifo_allocation() {
+    let resting = level(&[10, 20, 30]);
+    // FIFO stops reading the level once the quantity is placed
+    assert_eq!(Fifo.allocate(25, &mut resting.iter()), vec![10, 15]);
+    assert_eq!(Fifo.allocate(100, &mut resting.iter()), vec![10, 20, 30]);
+    assert!(Fifo.allocate(10, &mut level(&[]).iter()).is_empty());
+}
+
+#[test]
+fn test_pro_rata_allocation() {
+    let resting = level(&[10, 30, 60]);
+    assert_eq!(ProRata.allocate(50, &mut resting.iter()), vec![5, 15, 30]);
+    assert_eq!(ProRata.allocate(200, &mut resting.iter()), vec![10, 30, 60]);
+
+    // Lots lost to rounding go out in time priority
+    let resting = level(&[10, 10, 10]);
+    assert_eq!(ProRata.allocate(10, &mut resting.iter()), vec![4, 3, 3]);
+}
+
+#[test]
+fn test_pro_rata_top_order_allocation() {
+    let resting = level(&[10, 30, 60]);
+    assert_eq!(ProRataTopOrder.allocate(55, &mut resting.iter()), vec![10, 15, 30]);
+    assert_eq!(ProRataTopOrder.allocate(6, &mut resting.iter()), vec![6, 0, 0]);
+}
+
+#[test]
+fn test_lead_market_maker_allocation() {
+    let mut resting = level(&[50, 50, 100]);
+    resting[1] = OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 50)
+        .with_lead_market_maker()
+        .to_order(1);
+
+    // 40% of 100 goes to the market maker, the other 60 is shared pro-rata
+    // over the remaining 50, 10 and 100
+    let allocations = LeadMarketMaker::new(40).allocate(100, &mut resting.iter());
+    assert_eq!(allocations, vec![20, 43, 37]);
+    assert_eq!(allocations.iter().sum::<Quantity>(), 100);
+}
+
+#[test]
+fn test_lead_market_maker_percentage_is_capped() {
+    let policy: LeadMarketMaker = LeadMarketMaker::new(200);
+    assert_eq!(policy.get_percentage(), 100);
+
+    let resting: Vec<Order> = vec![OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 50)
+        .with_lead_market_maker()
+        .to_order(0)];
+    assert_eq!(policy.allocate(30, &mut resting.iter()), vec![30]);
+}
+
+#[test]
+fn test_allocation_uses_visible_quantity() {
+    let resting: Vec<Order> = vec![
+        OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 50).with_display_quantity(5).to_order(0),
+        Order::new(1, Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 15),
+    ];
+    assert_eq!(ProRata.allocate(40, &mut resting.iter()), vec![5, 15]);
+}
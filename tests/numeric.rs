@@ -0,0 +1,32 @@
+use my_order_book::{PriceValue, QuantityValue};
+
+#[test]
+fn test_price_value_conversions() {
+    assert_eq!(<i64 as PriceValue>::to_i128(-5), -5);
+    assert_eq!(<u64 as PriceValue>::from_i128(u64::MAX as i128), Some(u64::MAX));
+    assert_eq!(<u32 as PriceValue>::from_i128(-1), None);
+    assert_eq!(<u32 as PriceValue>::from_i128(u32::MAX as i128 + 1), None);
+    assert_eq!(<i64 as PriceValue>::from_i128(i64::MIN as i128), Some(i64::MIN));
+}
+
+#[test]
+fn test_price_value_saturation() {
+    assert_eq!(<u32 as PriceValue>::saturating_from_i128(-10), 0);
+    assert_eq!(<u32 as PriceValue>::saturating_from_i128(1 << 40), u32::MAX);
+    assert_eq!(<i64 as PriceValue>::saturating_from_i128(i128::MIN), i64::MIN);
+    assert_eq!(<u64 as PriceValue>::saturating_from_i128(7), 7);
+}
+
+#[test]
+fn test_quantity_value_arithmetic() {
+    assert_eq!(<u32 as QuantityValue>::from_u128(1 << 32), None);
+    assert_eq!(<u64 as QuantityValue>::from_u128(1 << 32), Some(1 << 32));
+    assert_eq!(<u32 as QuantityValue>::saturating_from_u128(1 << 40), u32::MAX);
+    assert_eq!(QuantityValue::saturating_sub(3u64, 5u64), 0);
+    assert_eq!(QuantityValue::saturating_sub(5u64, 3u64), 2);
+    assert_eq!(QuantityValue::checked_add(u32::MAX, 1u32), None);
+    assert_eq!(QuantityValue::checked_add(u32::MAX - 1, 1u32), Some(u32::MAX));
+    assert_eq!(QuantityValue::saturating_add(u64::MAX, 1u64), u64::MAX);
+    assert!(QuantityValue::is_multiple_of(30u32, 10u32));
+    assert!(!QuantityValue::is_multiple_of(35u64, 10u64));
+}
@@ -1,12 +1,12 @@
-use my_order_book::{Order, Side, OrderType};
+use my_order_book::{Order, Price, Quantity, Side, OrdType, TimeInForce};
 
 #[test]
 fn test_order_creation() {
-    let order = Order::new(1, Side::Buy, OrderType::Limit, 100, 10);
+    let order: Order = Order::new(1, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
     
     assert_eq!(order.get_order_id(), 1);
     assert_eq!(order.get_side(), Side::Buy);
-    assert_eq!(order.get_order_type(), OrderType::Limit);
+    assert_eq!((order.get_ord_type(), order.get_time_in_force()), (OrdType::Limit, TimeInForce::GoodTillCancel));
     assert_eq!(order.get_price(), 100);
     assert_eq!(order.get_initial_quantity(), 10);
     assert_eq!(order.get_remaining_quantity(), 10);
@@ -16,26 +16,26 @@ fn test_order_creation() {
 
 #[test]
 fn test_order_getters() {
-    let buy_order = Order::new(42, Side::Buy, OrderType::Limit, 150, 25);
+    let buy_order: Order = Order::new(42, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 150, 25);
     
     assert_eq!(buy_order.get_order_id(), 42);
     assert_eq!(buy_order.get_side(), Side::Buy);
-    assert_eq!(buy_order.get_order_type(), OrderType::Limit);
+    assert_eq!((buy_order.get_ord_type(), buy_order.get_time_in_force()), (OrdType::Limit, TimeInForce::GoodTillCancel));
     assert_eq!(buy_order.get_price(), 150);
     assert_eq!(buy_order.get_initial_quantity(), 25);
     
-    let sell_order = Order::new(99, Side::Sell, OrderType::Market, 0, 5);
+    let sell_order: Order = Order::new(99, Side::Sell, OrdType::Market, TimeInForce::GoodTillCancel, 0, 5);
     
     assert_eq!(sell_order.get_order_id(), 99);
     assert_eq!(sell_order.get_side(), Side::Sell);
-    assert_eq!(sell_order.get_order_type(), OrderType::Market);
+    assert_eq!((sell_order.get_ord_type(), sell_order.get_time_in_force()), (OrdType::Market, TimeInForce::GoodTillCancel));
     assert_eq!(sell_order.get_price(), 0);
     assert_eq!(sell_order.get_initial_quantity(), 5);
 }
 
 #[test]
 fn test_order_fill() {
-    let mut order = Order::new(1, Side::Buy, OrderType::Limit, 100, 10);
+    let mut order: Order = Order::new(1, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
     
     // Initially not filled
     assert!(!order.is_filled());
@@ -62,7 +62,7 @@ fn test_order_fill() {
 
 #[test]
 fn test_order_overfill_protection() {
-    let mut order = Order::new(1, Side::Buy, OrderType::Limit, 100, 10);
+    let mut order: Order = Order::new(1, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
     
     // Try to fill more than the order quantity
     order.fill(15);
@@ -75,7 +75,7 @@ fn test_order_overfill_protection() {
 
 #[test]
 fn test_order_multiple_fills_with_overfill() {
-    let mut order = Order::new(1, Side::Buy, OrderType::Limit, 100, 10);
+    let mut order: Order = Order::new(1, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
     
     // Partial fill
     order.fill(7);
@@ -92,16 +92,16 @@ fn test_order_multiple_fills_with_overfill() {
 }
 
 #[test]
-fn test_to_good_till_cancel() {
-    let mut market_order = Order::new(1, Side::Buy, OrderType::Market, 0, 10);
+fn test_to_limit() {
+    let mut market_order: Order = Order::new(1, Side::Buy, OrdType::Market, TimeInForce::GoodTillCancel, 0, 10);
     
-    assert_eq!(market_order.get_order_type(), OrderType::Market);
+    assert_eq!((market_order.get_ord_type(), market_order.get_time_in_force()), (OrdType::Market, TimeInForce::GoodTillCancel));
     assert_eq!(market_order.get_price(), 0);
     
-    // Convert to GTC with specific price
-    market_order.to_good_till_cancel(105);
+    // Convert to a limit order with specific price
+    market_order.to_limit(105);
     
-    assert_eq!(market_order.get_order_type(), OrderType::GoodTillCancel);
+    assert_eq!((market_order.get_ord_type(), market_order.get_time_in_force()), (OrdType::Limit, TimeInForce::GoodTillCancel));
     assert_eq!(market_order.get_price(), 105);
     
     // Other properties should remain unchanged
@@ -110,33 +110,42 @@ fn test_to_good_till_cancel() {
     assert_eq!(market_order.get_initial_quantity(), 10);
 }
 
+#[test]
+fn test_to_limit_keeps_time_in_force() {
+    let mut market_order: Order = Order::new(1, Side::Sell, OrdType::Market, TimeInForce::FillAndKill, 0, 10);
+    market_order.to_limit(95);
+
+    assert_eq!((market_order.get_ord_type(), market_order.get_time_in_force()), (OrdType::Limit, TimeInForce::FillAndKill));
+    assert_eq!(market_order.get_price(), 95);
+}
+
 #[test]
 fn test_order_types() {
-    let limit_order = Order::new(1, Side::Buy, OrderType::Limit, 100, 10);
-    assert_eq!(limit_order.get_order_type(), OrderType::Limit);
+    let limit_order: Order = Order::new(1, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    assert_eq!((limit_order.get_ord_type(), limit_order.get_time_in_force()), (OrdType::Limit, TimeInForce::GoodTillCancel));
     
-    let market_order = Order::new(2, Side::Sell, OrderType::Market, 0, 5);
-    assert_eq!(market_order.get_order_type(), OrderType::Market);
+    let market_order: Order = Order::new(2, Side::Sell, OrdType::Market, TimeInForce::GoodTillCancel, 0, 5);
+    assert_eq!((market_order.get_ord_type(), market_order.get_time_in_force()), (OrdType::Market, TimeInForce::GoodTillCancel));
     
-    let fok_order = Order::new(3, Side::Buy, OrderType::FillOrKill, 95, 15);
-    assert_eq!(fok_order.get_order_type(), OrderType::FillOrKill);
+    let fok_order: Order = Order::new(3, Side::Buy, OrdType::Limit, TimeInForce::FillOrKill, 95, 15);
+    assert_eq!((fok_order.get_ord_type(), fok_order.get_time_in_force()), (OrdType::Limit, TimeInForce::FillOrKill));
     
-    let gtc_order = Order::new(4, Side::Sell, OrderType::GoodTillCancel, 110, 8);
-    assert_eq!(gtc_order.get_order_type(), OrderType::GoodTillCancel);
+    let gtc_order: Order = Order::new(4, Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 110, 8);
+    assert_eq!((gtc_order.get_ord_type(), gtc_order.get_time_in_force()), (OrdType::Limit, TimeInForce::GoodTillCancel));
 }
 
 #[test]
 fn test_order_sides() {
-    let buy_order = Order::new(1, Side::Buy, OrderType::Limit, 100, 10);
+    let buy_order: Order = Order::new(1, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
     assert_eq!(buy_order.get_side(), Side::Buy);
     
-    let sell_order = Order::new(2, Side::Sell, OrderType::Limit, 105, 5);
+    let sell_order: Order = Order::new(2, Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 5);
     assert_eq!(sell_order.get_side(), Side::Sell);
 }
 
 #[test]
 fn test_order_clone_and_equality() {
-    let order1 = Order::new(1, Side::Buy, OrderType::Limit, 100, 10);
+    let order1: Order = Order::new(1, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
     let order2 = order1.clone();
     
     assert_eq!(order1, order2);
@@ -148,7 +157,7 @@ fn test_order_clone_and_equality() {
 
 #[test]
 fn test_order_inequality_after_fill() {
-    let mut order1 = Order::new(1, Side::Buy, OrderType::Limit, 100, 10);
+    let mut order1: Order = Order::new(1, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
     let order2 = order1.clone();
     
     // Initially equal
@@ -159,3 +168,114 @@ fn test_order_inequality_after_fill() {
     assert_ne!(order1, order2);
     assert_ne!(order1.filled_quantity, order2.filled_quantity);
 }
+
+#[test]
+fn test_order_is_stop() {
+    assert!(Order::<Price, Quantity>::new(1, Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 10).is_stop());
+    assert!(Order::<Price, Quantity>::new(2, Side::Buy, OrdType::StopLimit, TimeInForce::GoodTillCancel, 100, 10).is_stop());
+    assert!(!Order::<Price, Quantity>::new(3, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10).is_stop());
+    assert!(!Order::<Price, Quantity>::new(4, Side::Buy, OrdType::Market, TimeInForce::GoodTillCancel, 0, 10).is_stop());
+}
+
+#[test]
+fn test_order_trigger() {
+    let mut stop: Order = Order::new(1, Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 10);
+    stop.trigger();
+    assert_eq!((stop.get_ord_type(), stop.get_time_in_force()), (OrdType::Market, TimeInForce::GoodTillCancel));
+
+    let mut stop_limit: Order = Order::new(2, Side::Sell, OrdType::StopLimit, TimeInForce::GoodTillCancel, 95, 10);
+    stop_limit.trigger();
+    assert_eq!((stop_limit.get_ord_type(), stop_limit.get_time_in_force()), (OrdType::Limit, TimeInForce::GoodTillCancel));
+    assert_eq!(stop_limit.get_price(), 95);
+
+    // Non-stop orders are unchanged
+    let mut limit: Order = Order::new(3, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    limit.trigger();
+    assert_eq!((limit.get_ord_type(), limit.get_time_in_force()), (OrdType::Limit, TimeInForce::GoodTillCancel));
+}
+
+#[test]
+fn test_iceberg_visible_quantity() {
+    let mut order: Order = Order::new(1, Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 25);
+    assert!(!order.is_iceberg());
+    assert_eq!(order.get_visible_quantity(), 25);
+
+    order.set_display_quantity(10);
+    assert!(order.is_iceberg());
+    assert_eq!(order.get_display_quantity(), Some(10));
+    assert_eq!(order.get_visible_quantity(), 10);
+    assert_eq!(order.get_remaining_quantity(), 25);
+}
+
+#[test]
+fn test_iceberg_fill_and_replenish() {
+    let mut order: Order = Order::new(1, Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 25);
+    order.set_display_quantity(10);
+
+    order.fill(4);
+    assert_eq!(order.get_visible_quantity(), 6);
+    assert!(!order.needs_replenish());
+
+    order.fill(6);
+    assert_eq!(order.get_visible_quantity(), 0);
+    assert!(order.needs_replenish());
+
+    order.replenish();
+    assert_eq!(order.get_visible_quantity(), 10);
+
+    // Last slice is capped by the remaining quantity
+    order.fill(10);
+    order.replenish();
+    assert_eq!(order.get_visible_quantity(), 5);
+
+    order.fill(5);
+    assert!(order.is_filled());
+    assert!(!order.needs_replenish());
+}
+
+#[test]
+fn test_iceberg_display_larger_than_quantity() {
+    let mut order: Order = Order::new(1, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+    order.set_display_quantity(10);
+    assert_eq!(order.get_visible_quantity(), 5);
+}
+
+#[test]
+fn test_trailing_stop_price_ratchets() {
+    use my_order_book::TrailAmount;
+
+    let mut sell: Order = Order::new(1, Side::Sell, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 10);
+    sell.trail = Some(TrailAmount::Absolute(5));
+    assert_eq!(sell.trailing_stop_price(100, 1), Some(95));
+
+    sell.stop_price = Some(95);
+    assert_eq!(sell.trailing_stop_price(110, 1), Some(105));
+    // A falling price never lowers a sell stop
+    assert_eq!(sell.trailing_stop_price(98, 1), None);
+
+    let mut buy: Order = Order::new(2, Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 10);
+    buy.trail = Some(TrailAmount::BasisPoints(1_000));
+    buy.stop_price = Some(110);
+    assert_eq!(buy.trailing_stop_price(80, 1), Some(88));
+    // A rising price never raises a buy stop
+    assert_eq!(buy.trailing_stop_price(105, 1), None);
+
+    // A trail that rounds to nothing still keeps the minimum distance
+    let mut tight: Order = Order::new(4, Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 10);
+    tight.trail = Some(TrailAmount::BasisPoints(10));
+    assert_eq!(tight.trailing_stop_price(50, 1), Some(51));
+
+    // Orders without a trail never move
+    let plain: Order = Order::new(3, Side::Sell, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 10);
+    assert_eq!(plain.trailing_stop_price(100, 1), None);
+}
+
+#[test]
+fn test_fill_never_overflows() {
+    let mut order: Order = Order::new(1, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, Quantity::MAX);
+    order.fill(Quantity::MAX - 1);
+    order.fill(10);
+    assert!(order.is_filled());
+    assert_eq!(order.filled_quantity, Quantity::MAX);
+    assert_eq!(order.get_remaining_quantity(), 0);
+}
@@ -1,9 +1,16 @@
-use my_order_book::{OrderBook, OrderModify, Side, OrderType, OrderStatus};
+use my_order_book::{OrderBook, OrderbookLevelInfos, OrderId, ArrayLadder, Trade, MassCancel, CancelRejectReason, Price, Quantity, PriceScale, InstrumentRules, TickTable, BreachAction, PriceBand, BookEvent, TradingPhase, ProRata, ProRataTopOrder, OrderModify, OrderRequest, Side, OrdType, TimeInForce, OrderStatus, ManualClock, MarketSchedule, CancelReason, PostOnly, Peg, PegType, TrailAmount};
+use my_order_book::clock::NANOS_PER_SECOND;
+
+const HOUR: u64 = 60 * 60 * NANOS_PER_SECOND;
+
+fn level_order_ids(ob: &OrderBook, side: Side, price: Price) -> Vec<OrderId> {
+    ob.get_level_orders(side, price).map(|order| order.get_order_id()).collect()
+}
 
 #[test]
 fn can_add_order_and_query_bbo() {
     let mut ob = OrderBook::new();
-    let (id, trades) = ob.add_order(Side::Buy, OrderType::Limit, 100, 5);
+    let (id, trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
     assert_eq!(id, 0);
     assert!(trades.is_empty());
     assert_eq!(ob.get_best_bid(), Some(100));
@@ -15,8 +22,8 @@ fn test_basic_order_matching() {
     let mut ob = OrderBook::new();
     
     // Add non-crossing orders
-    let (buy_id, trades1) = ob.add_order(Side::Buy, OrderType::Limit, 100, 10);
-    let (sell_id, trades2) = ob.add_order(Side::Sell, OrderType::Limit, 105, 5);
+    let (buy_id, trades1) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let (sell_id, trades2) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 5);
     
     assert_eq!(buy_id, 0);
     assert_eq!(sell_id, 1);
@@ -24,7 +31,7 @@ fn test_basic_order_matching() {
     assert!(trades2.is_empty());
     
     // Add crossing order
-    let (crossing_id, trades3) = ob.add_order(Side::Buy, OrderType::Limit, 105, 3);
+    let (crossing_id, trades3) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 3);
     assert_eq!(crossing_id, 2);
     assert_eq!(trades3.len(), 1);
     
@@ -40,10 +47,10 @@ fn test_market_orders() {
     let mut ob = OrderBook::new();
     
     // Add limit order first
-    let (_, _) = ob.add_order(Side::Sell, OrderType::Limit, 105, 10);
+    let (_, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
     
     // Add market buy order - should convert to limit at worst ask
-    let (market_id, trades) = ob.add_order(Side::Buy, OrderType::Market, 0, 5);
+    let (market_id, trades) = ob.add_order(Side::Buy, OrdType::Market, TimeInForce::GoodTillCancel, 0, 5);
     assert_eq!(market_id, 1);
     assert_eq!(trades.len(), 1);
     
@@ -57,16 +64,16 @@ fn test_fill_or_kill() {
     let mut ob = OrderBook::new();
     
     // Add partial liquidity
-    let (_, _) = ob.add_order(Side::Sell, OrderType::Limit, 105, 5);
+    let (_, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 5);
     
     // Try Fill or Kill for more than available - should be rejected
-    let (fok_id, trades) = ob.add_order(Side::Buy, OrderType::FillOrKill, 105, 10);
+    let (fok_id, trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::FillOrKill, 105, 10);
     assert_eq!(fok_id, 1);
     assert!(trades.is_empty());
     assert_eq!(ob.size(), 1); // Only the original sell order remains
     
     // Try Fill or Kill for exact amount - should work
-    let (fok_id2, trades2) = ob.add_order(Side::Buy, OrderType::FillOrKill, 105, 5);
+    let (fok_id2, trades2) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::FillOrKill, 105, 5);
     assert_eq!(fok_id2, 2);
     assert_eq!(trades2.len(), 1);
     assert_eq!(ob.size(), 0); // Both orders should be filled
@@ -77,13 +84,15 @@ fn test_order_modification() {
     let mut ob = OrderBook::new();
     
     // Add an order
-    let (order_id, _) = ob.add_order(Side::Buy, OrderType::Limit, 100, 10);
+    let (order_id, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
     assert_eq!(ob.size(), 1);
     
     // Modify the order
-    let order_modify = OrderModify::new(order_id, Side::Buy, 101, 15);
-    let trades = ob.modify_order(order_modify);
-    assert!(trades.is_empty());
+    let order_modify: OrderModify = OrderModify::new(order_id, Side::Buy, 101, 15);
+    let result = ob.modify_order(order_modify);
+    assert!(result.trades.is_empty());
+    assert_eq!(result.order_id, order_id);
+    assert_eq!(result.status, OrderStatus::Accepted);
     assert_eq!(ob.size(), 1);
     assert_eq!(ob.get_best_bid(), Some(101));
 }
@@ -93,18 +102,18 @@ fn test_order_cancellation() {
     let mut ob = OrderBook::new();
     
     // Add orders
-    let (buy_id, _) = ob.add_order(Side::Buy, OrderType::Limit, 100, 10);
-    let (sell_id, _) = ob.add_order(Side::Sell, OrderType::Limit, 105, 5);
+    let (buy_id, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let (sell_id, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 5);
     assert_eq!(ob.size(), 2);
     
     // Cancel buy order
-    ob.cancel_order(buy_id);
+    assert!(ob.cancel_order(buy_id).is_ok());
     assert_eq!(ob.size(), 1);
     assert_eq!(ob.get_best_bid(), None);
     assert_eq!(ob.get_best_ask(), Some(105));
     
     // Cancel sell order
-    ob.cancel_order(sell_id);
+    assert!(ob.cancel_order(sell_id).is_ok());
     assert_eq!(ob.size(), 0);
     assert_eq!(ob.get_best_ask(), None);
 }
@@ -114,10 +123,10 @@ fn test_order_book_levels() {
     let mut ob = OrderBook::new();
     
     // Add multiple orders at different levels
-    let (_, _) = ob.add_order(Side::Buy, OrderType::Limit, 100, 10);
-    let (_, _) = ob.add_order(Side::Buy, OrderType::Limit, 99, 5);
-    let (_, _) = ob.add_order(Side::Sell, OrderType::Limit, 105, 8);
-    let (_, _) = ob.add_order(Side::Sell, OrderType::Limit, 106, 3);
+    let (_, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let (_, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 5);
+    let (_, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 8);
+    let (_, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 106, 3);
     
     let level_infos = ob.get_order_infos();
     
@@ -141,10 +150,10 @@ fn test_partial_fills() {
     let mut ob = OrderBook::new();
     
     // Add large sell order
-    let (_sell_id, _) = ob.add_order(Side::Sell, OrderType::Limit, 105, 20);
+    let (_sell_id, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 20);
     
     // Add smaller buy order - should partially fill
-    let (_buy_id, trades) = ob.add_order(Side::Buy, OrderType::Limit, 105, 8);
+    let (_buy_id, trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 8);
     
     assert_eq!(trades.len(), 1);
     assert_eq!(trades[0].bid_info.quantity, 8);
@@ -161,12 +170,12 @@ fn test_order_status_accepted() {
     let mut ob = OrderBook::new();
 
     // Non-crossing orders should be accepted
-    let result1 = ob.add_order_with_status(Side::Buy, OrderType::Limit, 100, 10);
+    let result1 = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
     assert_eq!(result1.status, OrderStatus::Accepted);
     assert_eq!(result1.order_id, 0);
     assert!(result1.trades.is_empty());
 
-    let result2 = ob.add_order_with_status(Side::Sell, OrderType::Limit, 105, 5);
+    let result2 = ob.add_order_with_status(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 5);
     assert_eq!(result2.status, OrderStatus::Accepted);
     assert_eq!(result2.order_id, 1);
     assert!(result2.trades.is_empty());
@@ -177,11 +186,11 @@ fn test_order_status_executed() {
     let mut ob = OrderBook::new();
 
     // Add a sell order first
-    let result1 = ob.add_order_with_status(Side::Sell, OrderType::Limit, 100, 10);
+    let result1 = ob.add_order_with_status(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
     assert_eq!(result1.status, OrderStatus::Accepted);
 
     // Add crossing buy order - should execute
-    let result2 = ob.add_order_with_status(Side::Buy, OrderType::Limit, 100, 5);
+    let result2 = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
     assert_eq!(result2.status, OrderStatus::Executed);
     assert_eq!(result2.trades.len(), 1);
     assert_eq!(result2.trades[0].bid_info.order_id, 1);
@@ -193,13 +202,13 @@ fn test_order_status_rejected_no_liquidity() {
     let mut ob = OrderBook::new();
 
     // Market buy with no asks should be rejected
-    let result = ob.add_order_with_status(Side::Buy, OrderType::Market, 0, 10);
+    let result = ob.add_order_with_status(Side::Buy, OrdType::Market, TimeInForce::GoodTillCancel, 0, 10);
     assert_eq!(result.status, OrderStatus::RejectedNoLiquidity);
     assert!(result.trades.is_empty());
     assert_eq!(ob.size(), 0);
 
     // Market sell with no bids should be rejected
-    let result2 = ob.add_order_with_status(Side::Sell, OrderType::Market, 0, 10);
+    let result2 = ob.add_order_with_status(Side::Sell, OrdType::Market, TimeInForce::GoodTillCancel, 0, 10);
     assert_eq!(result2.status, OrderStatus::RejectedNoLiquidity);
     assert!(result2.trades.is_empty());
     assert_eq!(ob.size(), 0);
@@ -210,10 +219,10 @@ fn test_order_status_rejected_fill_and_kill_no_match() {
     let mut ob = OrderBook::new();
 
     // Add a sell order at 105
-    ob.add_order_with_status(Side::Sell, OrderType::Limit, 105, 10);
+    ob.add_order_with_status(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
 
     // Fill-and-Kill buy at 100 (below ask) should be rejected
-    let result = ob.add_order_with_status(Side::Buy, OrderType::FillAndKill, 100, 5);
+    let result = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::FillAndKill, 100, 5);
     assert_eq!(result.status, OrderStatus::RejectedFillAndKillNoMatch);
     assert!(result.trades.is_empty());
     assert_eq!(ob.size(), 1); // Only the original sell order remains
@@ -224,16 +233,16 @@ fn test_order_status_rejected_fill_or_kill_partial_fill() {
     let mut ob = OrderBook::new();
 
     // Add partial liquidity - only 5 shares available
-    ob.add_order_with_status(Side::Sell, OrderType::Limit, 100, 5);
+    ob.add_order_with_status(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
 
     // Fill-or-Kill for 10 shares (more than available) should be rejected
-    let result = ob.add_order_with_status(Side::Buy, OrderType::FillOrKill, 100, 10);
+    let result = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::FillOrKill, 100, 10);
     assert_eq!(result.status, OrderStatus::RejectedFillOrKillPartialFill);
     assert!(result.trades.is_empty());
     assert_eq!(ob.size(), 1); // Only the original sell order remains
 
     // Fill-or-Kill for exact amount should work
-    let result2 = ob.add_order_with_status(Side::Buy, OrderType::FillOrKill, 100, 5);
+    let result2 = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::FillOrKill, 100, 5);
     assert_eq!(result2.status, OrderStatus::Executed);
     assert_eq!(result2.trades.len(), 1);
     assert_eq!(ob.size(), 0); // Both orders should be filled
@@ -248,6 +257,26 @@ fn test_order_status_messages() {
     assert_eq!(OrderStatus::RejectedFillAndKillNoMatch.message(), "Fill-and-Kill order rejected: no matching orders available");
     assert_eq!(OrderStatus::RejectedFillOrKillPartialFill.message(), "Fill-or-Kill order rejected: insufficient liquidity for complete fill");
     assert_eq!(OrderStatus::RejectedDuplicateId.message(), "Order rejected: duplicate order ID");
+    assert_eq!(OrderStatus::PendingTrigger.message(), "Stop order accepted and waiting for stop price");
+    assert_eq!(OrderStatus::RejectedMissingStopPrice.message(), "Stop order rejected: no stop price specified");
+    assert_eq!(OrderStatus::RejectedMissingExpiry.message(), "Good-Till-Date order rejected: no expiry specified");
+    assert_eq!(OrderStatus::RejectedExpired.message(), "Good-Till-Date order rejected: expiry has already passed");
+    assert_eq!(OrderStatus::RejectedInvalidDisplayQuantity.message(), "Iceberg order rejected: display quantity must be greater than zero");
+    assert_eq!(OrderStatus::Repriced.message(), "Post-only order repriced to avoid crossing and placed in order book");
+    assert_eq!(OrderStatus::RejectedPostOnlyWouldCross.message(), "Post-only order rejected: order would take liquidity");
+    assert_eq!(OrderStatus::RejectedNoPegReference.message(), "Pegged order rejected: no reference price available");
+    assert_eq!(OrderStatus::RejectedInvalidTrailAmount.message(), "Trailing stop rejected: trail amount must be greater than zero");
+    assert_eq!(OrderStatus::RejectedInvalidCombination.message(), "Order rejected: order type, time in force and instructions are incompatible");
+    assert_eq!(OrderStatus::RejectedImmediateOrderInAuction.message(), "Order rejected: immediate orders are not accepted during an auction");
+    assert_eq!(OrderStatus::RejectedTradingHalted.message(), "Order rejected: trading is halted, only cancels are accepted");
+    assert_eq!(OrderStatus::RejectedMarketClosed.message(), "Order rejected: market is closed");
+    assert_eq!(OrderStatus::RejectedInvalidQuantity.message(), "Order rejected: quantity must be greater than zero");
+    assert_eq!(OrderStatus::RejectedInvalidPrice.message(), "Order rejected: limit price is outside the instrument's price range");
+    assert_eq!(OrderStatus::RejectedPriceNotOnTick.message(), "Order rejected: price is not a multiple of the tick size");
+    assert_eq!(OrderStatus::RejectedQuantityNotOnLot.message(), "Order rejected: quantity is not a multiple of the lot size");
+    assert_eq!(OrderStatus::RejectedBelowMinimumQuantity.message(), "Order rejected: quantity is below the minimum order size");
+    assert_eq!(OrderStatus::RejectedAboveMaximumQuantity.message(), "Order rejected: quantity is above the maximum order size");
+    assert_eq!(OrderStatus::RejectedQuantityOverflow.message(), "Order rejected: total quantity at the price level would overflow");
 }
 
 #[test]
@@ -255,8 +284,8 @@ fn test_mixed_order_methods() {
     let mut ob = OrderBook::new();
 
     // Test that both old and new methods work together
-    let (old_id, old_trades) = ob.add_order(Side::Buy, OrderType::Limit, 100, 10);
-    let new_result = ob.add_order_with_status(Side::Sell, OrderType::Limit, 105, 5);
+    let (old_id, old_trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let new_result = ob.add_order_with_status(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 5);
 
     assert_eq!(old_id, 0);
     assert!(old_trades.is_empty());
@@ -265,7 +294,1690 @@ fn test_mixed_order_methods() {
     assert!(new_result.trades.is_empty());
 
     // Add crossing order with new method
-    let crossing_result = ob.add_order_with_status(Side::Buy, OrderType::Limit, 106, 3);
+    let crossing_result = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 106, 3);
     assert_eq!(crossing_result.status, OrderStatus::Executed);
     assert_eq!(crossing_result.trades.len(), 1);
 }
+
+#[test]
+fn test_stop_order_waits_for_trigger() {
+    let mut ob = OrderBook::new();
+
+    let result = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 5).with_stop_price(105));
+    assert_eq!(result.status, OrderStatus::PendingTrigger);
+    assert!(result.trades.is_empty());
+
+    // Untriggered stops do not rest in the visible book
+    assert_eq!(ob.size(), 0);
+    assert_eq!(ob.stop_book.len(), 1);
+    assert_eq!(ob.get_best_bid(), None);
+}
+
+#[test]
+fn test_stop_order_missing_stop_price() {
+    let mut ob = OrderBook::new();
+
+    let result = ob.add_order_with_status(Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 5);
+    assert_eq!(result.status, OrderStatus::RejectedMissingStopPrice);
+    assert!(ob.stop_book.is_empty());
+}
+
+#[test]
+fn test_buy_stop_triggers_as_market_order() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 104, 1);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 106, 10);
+    let stop = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 5).with_stop_price(104));
+    assert_eq!(stop.status, OrderStatus::PendingTrigger);
+
+    // Trade at 104 triggers the stop, which buys from the 106 offer
+    let result = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 104, 1);
+    assert_eq!(result.status, OrderStatus::Executed);
+    assert_eq!(result.trades.len(), 2);
+    assert_eq!(result.trades[1].bid_info.order_id, stop.order_id);
+    assert_eq!(result.trades[1].ask_info.price, 106);
+    assert_eq!(result.trades[1].ask_info.quantity, 5);
+
+    assert!(ob.stop_book.is_empty());
+    assert_eq!(ob.get_last_trade_price(), Some(106));
+    assert_eq!(ob.get_order_infos().asks[0].quantity, 5);
+}
+
+#[test]
+fn test_stop_limit_triggers_as_limit_order() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 1);
+    let stop = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::StopLimit, TimeInForce::GoodTillCancel, 99, 5).with_stop_price(100));
+    assert_eq!(stop.status, OrderStatus::PendingTrigger);
+
+    let result = ob.add_order_with_status(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 1);
+    assert_eq!(result.trades.len(), 1);
+
+    // Released as a limit sell at 99 with nothing to match, so it rests
+    assert!(ob.stop_book.is_empty());
+    assert_eq!(ob.get_best_ask(), Some(99));
+    assert_eq!(ob.get_order(stop.order_id).unwrap().get_ord_type(), OrdType::Limit);
+}
+
+#[test]
+fn test_stop_orders_cascade() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 1);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 98, 3);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 96, 5);
+    ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 5).with_stop_price(99));
+    ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 5).with_stop_price(97));
+
+    // Trade at 99 triggers the first stop, which sells down to 96 and
+    // triggers the second stop in the same call
+    let result = ob.add_order_with_status(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 1);
+    assert_eq!(result.trades.len(), 4);
+    assert!(ob.stop_book.is_empty());
+    assert_eq!(ob.get_last_trade_price(), Some(96));
+
+    // Second stop sold the remaining 3 at 96 and rests 2 at 96
+    assert_eq!(ob.get_best_bid(), None);
+    assert_eq!(ob.get_best_ask(), Some(96));
+    assert_eq!(ob.get_order_infos().asks[0].quantity, 2);
+}
+
+#[test]
+fn test_stop_triggered_by_price_traded_through_in_one_sweep() {
+    let mut ob = OrderBook::new();
+    trade_at(&mut ob, 105);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 95, 10);
+    let stop = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 5).with_stop_price(100)).order_id;
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 1);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 1);
+
+    // The sweep prints 99 then 101, so the market traded through the stop
+    let result = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 2);
+    assert_eq!(result.trades.len(), 3);
+    assert_eq!(result.trades[2].ask_info.order_id, stop);
+    assert_eq!(result.trades[2].bid_info.price, 95);
+    assert!(ob.stop_book.is_empty());
+    assert_eq!(ob.get_last_trade_price(), Some(95));
+}
+
+#[test]
+fn test_stop_order_already_triggered_on_submission() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 1);
+    assert_eq!(ob.get_last_trade_price(), Some(100));
+
+    let result = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 3).with_stop_price(99));
+    assert_eq!(result.status, OrderStatus::Executed);
+    assert_eq!(result.trades.len(), 1);
+    assert!(ob.stop_book.is_empty());
+}
+
+#[test]
+fn test_cancel_stop_order() {
+    let mut ob = OrderBook::new();
+
+    let stop = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 5).with_stop_price(105));
+    assert_eq!(ob.stop_book.len(), 1);
+
+    assert!(ob.cancel_order(stop.order_id).is_ok());
+    assert!(ob.stop_book.is_empty());
+}
+
+#[test]
+fn test_good_for_day_orders_cancelled_at_market_close() {
+    let clock = ManualClock::new(9 * HOUR);
+    let mut ob = OrderBook::with_clock(Box::new(clock.clone()));
+    ob.set_market_schedule(MarketSchedule::daily_close(16, 0));
+    assert_eq!(ob.get_next_market_close(), Some(16 * HOUR));
+
+    let (gfd_bid, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodForDay, 100, 10);
+    let (gtc_bid, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 10);
+    let (gfd_ask, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodForDay, 105, 10);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 4);
+
+    // Before close nothing is cancelled
+    clock.set(15 * HOUR);
+    assert!(ob.prune_good_for_day_orders().is_empty());
+    assert_eq!(ob.size(), 3);
+
+    clock.set(16 * HOUR);
+    let cancelled = ob.prune_good_for_day_orders();
+    assert_eq!(cancelled.len(), 2);
+    assert_eq!(cancelled[0].order_id, gfd_bid);
+    assert_eq!(cancelled[0].side, Side::Buy);
+    assert_eq!(cancelled[0].remaining_quantity, 10);
+    assert_eq!(cancelled[1].order_id, gfd_ask);
+    assert_eq!(cancelled[1].remaining_quantity, 6);
+    assert!(cancelled.iter().all(|event| event.reason == CancelReason::MarketClose));
+    assert!(cancelled.iter().all(|event| event.timestamp == 16 * HOUR));
+
+    assert_eq!(ob.size(), 1);
+    assert!(ob.get_order(gtc_bid).is_some());
+    assert_eq!(ob.get_best_ask(), None);
+
+    // The next close is tomorrow
+    assert_eq!(ob.get_next_market_close(), Some(40 * HOUR));
+    assert!(ob.prune_good_for_day_orders().is_empty());
+}
+
+#[test]
+fn test_good_for_day_orders_kept_without_schedule() {
+    let clock = ManualClock::new(0);
+    let mut ob = OrderBook::with_clock(Box::new(clock.clone()));
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodForDay, 100, 10);
+    clock.advance(48 * HOUR);
+
+    assert!(ob.prune_good_for_day_orders().is_empty());
+    assert_eq!(ob.size(), 1);
+}
+
+#[test]
+fn test_good_for_day_orders_expire_each_day() {
+    let clock = ManualClock::new(10 * HOUR);
+    let mut ob = OrderBook::with_clock(Box::new(clock.clone()));
+    ob.set_market_schedule(MarketSchedule::daily_close(16, 0));
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodForDay, 100, 10);
+    clock.set(17 * HOUR);
+    assert_eq!(ob.prune_good_for_day_orders().len(), 1);
+
+    // Order entered after close lives until the next day's close
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodForDay, 100, 10);
+    clock.set(34 * HOUR);
+    assert!(ob.prune_good_for_day_orders().is_empty());
+    clock.set(40 * HOUR);
+    assert_eq!(ob.prune_good_for_day_orders().len(), 1);
+    assert_eq!(ob.size(), 0);
+}
+
+#[test]
+fn test_good_till_date_validation() {
+    let clock = ManualClock::new(10 * HOUR);
+    let mut ob = OrderBook::with_clock(Box::new(clock.clone()));
+
+    let missing = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillDate, 100, 10);
+    assert_eq!(missing.status, OrderStatus::RejectedMissingExpiry);
+
+    let expired = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillDate, 100, 10).with_expiry(10 * HOUR));
+    assert_eq!(expired.status, OrderStatus::RejectedExpired);
+
+    let accepted = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillDate, 100, 10).with_expiry(11 * HOUR));
+    assert_eq!(accepted.status, OrderStatus::Accepted);
+    assert_eq!(ob.size(), 1);
+}
+
+#[test]
+fn test_good_till_date_orders_expire() {
+    let clock = ManualClock::new(0);
+    let mut ob = OrderBook::with_clock(Box::new(clock.clone()));
+
+    let early = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillDate, 100, 10).with_expiry(2 * HOUR));
+    let late = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillDate, 105, 10).with_expiry(5 * HOUR));
+    let middle = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillDate, 99, 10).with_expiry(3 * HOUR));
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 98, 10);
+
+    clock.set(HOUR);
+    assert!(ob.expire_orders().is_empty());
+
+    // Expiry is inclusive and cancellations come out in expiry order
+    clock.set(3 * HOUR);
+    let cancelled = ob.expire_orders();
+    assert_eq!(cancelled.len(), 2);
+    assert_eq!(cancelled[0].order_id, early.order_id);
+    assert_eq!(cancelled[0].timestamp, 2 * HOUR);
+    assert_eq!(cancelled[1].order_id, middle.order_id);
+    assert_eq!(cancelled[1].timestamp, 3 * HOUR);
+    assert!(cancelled.iter().all(|event| event.reason == CancelReason::Expired));
+    assert_eq!(ob.size(), 2);
+    assert_eq!(ob.get_best_bid(), Some(98));
+
+    clock.set(10 * HOUR);
+    let cancelled = ob.expire_orders();
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0].order_id, late.order_id);
+    assert_eq!(ob.size(), 1);
+}
+
+#[test]
+fn test_good_till_date_filled_or_cancelled_orders_do_not_expire() {
+    let clock = ManualClock::new(0);
+    let mut ob = OrderBook::with_clock(Box::new(clock.clone()));
+
+    let filled = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillDate, 100, 5).with_expiry(HOUR));
+    let partial = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillDate, 101, 5).with_expiry(HOUR));
+    let cancelled = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillDate, 102, 5).with_expiry(HOUR));
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 7);
+    assert!(ob.cancel_order(cancelled.order_id).is_ok());
+    assert!(ob.get_order(filled.order_id).is_none());
+
+    clock.set(HOUR);
+    let expired = ob.expire_orders();
+    assert_eq!(expired.len(), 1);
+    assert_eq!(expired[0].order_id, partial.order_id);
+    assert_eq!(expired[0].remaining_quantity, 3);
+    assert_eq!(ob.size(), 0);
+}
+
+#[test]
+fn test_process_expirations() {
+    let clock = ManualClock::new(9 * HOUR);
+    let mut ob = OrderBook::with_clock(Box::new(clock.clone()));
+    ob.set_market_schedule(MarketSchedule::daily_close(16, 0));
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodForDay, 100, 10);
+    ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillDate, 99, 10).with_expiry(12 * HOUR));
+    ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillDate, 98, 10).with_expiry(30 * HOUR));
+
+    clock.set(16 * HOUR);
+    let cancelled = ob.process_expirations();
+    assert_eq!(cancelled.len(), 2);
+    assert_eq!(cancelled[0].reason, CancelReason::Expired);
+    assert_eq!(cancelled[1].reason, CancelReason::MarketClose);
+
+    // Good Till Date orders survive the close
+    assert_eq!(ob.size(), 1);
+    assert_eq!(ob.get_best_bid(), Some(98));
+}
+
+#[test]
+fn test_iceberg_publishes_only_peak() {
+    let mut ob = OrderBook::new();
+
+    ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 100).with_display_quantity(10));
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+    ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 95, 50).with_display_quantity(20));
+
+    let level_infos = ob.get_order_infos();
+    assert_eq!(level_infos.asks[0].quantity, 15);
+    assert_eq!(level_infos.bids[0].quantity, 20);
+}
+
+#[test]
+fn test_iceberg_rejects_zero_display_quantity() {
+    let mut ob = OrderBook::new();
+
+    let result = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 100).with_display_quantity(0));
+    assert_eq!(result.status, OrderStatus::RejectedInvalidDisplayQuantity);
+    assert_eq!(ob.size(), 0);
+}
+
+#[test]
+fn test_iceberg_replenished_slice_loses_priority() {
+    let mut ob = OrderBook::new();
+
+    let iceberg = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 30).with_display_quantity(10));
+    let (plain, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+
+    let (_, trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 12);
+    assert_eq!(trades.len(), 2);
+    assert_eq!(trades[0].ask_info.order_id, iceberg.order_id);
+    assert_eq!(trades[0].ask_info.quantity, 10);
+    assert_eq!(trades[1].ask_info.order_id, plain);
+    assert_eq!(trades[1].ask_info.quantity, 2);
+
+    assert_eq!(level_order_ids(&ob, Side::Sell, 100), vec![plain, iceberg.order_id]);
+    assert_eq!(ob.get_order_infos().asks[0].quantity, 13);
+    assert_eq!(ob.get_order(iceberg.order_id).unwrap().get_remaining_quantity(), 20);
+}
+
+#[test]
+fn test_aggressor_sweeps_iceberg_slices() {
+    let mut ob = OrderBook::new();
+
+    let iceberg = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 30).with_display_quantity(10));
+
+    let (_, trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 25);
+    let quantities: Vec<Quantity> = trades.iter().map(|trade| trade.ask_info.quantity).collect();
+    assert_eq!(quantities, vec![10, 10, 5]);
+
+    let resting = ob.get_order(iceberg.order_id).unwrap();
+    assert_eq!(resting.get_remaining_quantity(), 5);
+    assert_eq!(resting.get_visible_quantity(), 5);
+    assert_eq!(ob.get_order_infos().asks[0].quantity, 5);
+}
+
+#[test]
+fn test_aggressive_iceberg_trades_hidden_quantity() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 50);
+    let iceberg = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 80).with_display_quantity(10));
+
+    assert_eq!(iceberg.trades.len(), 1);
+    assert_eq!(iceberg.trades[0].bid_info.quantity, 50);
+
+    // Remainder rests showing a full peak
+    let resting = ob.get_order(iceberg.order_id).unwrap();
+    assert_eq!(resting.get_remaining_quantity(), 30);
+    assert_eq!(ob.get_order_infos().bids[0].quantity, 10);
+}
+
+#[test]
+fn test_fill_or_kill_sees_hidden_iceberg_quantity() {
+    let mut ob = OrderBook::new();
+
+    ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 30).with_display_quantity(10));
+
+    let result = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::FillOrKill, 100, 25);
+    assert_eq!(result.status, OrderStatus::Executed);
+    assert_eq!(result.trades.len(), 3);
+}
+
+#[test]
+fn test_fill_or_kill_only_counts_opposite_levels_within_limit() {
+    let mut ob = OrderBook::new();
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 104, 10);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 106, 10);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 103, 50);
+
+    // Neither the ask beyond the limit nor the bids count towards the fill
+    let result = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::FillOrKill, 105, 15);
+    assert_eq!(result.status, OrderStatus::RejectedFillOrKillPartialFill);
+    assert_eq!(ob.size(), 3);
+
+    let result = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::FillOrKill, 106, 20);
+    assert_eq!(result.status, OrderStatus::Executed);
+    assert_eq!(ob.size(), 1);
+}
+
+#[test]
+fn test_available_liquidity_and_depth() {
+    let mut ob = OrderBook::new();
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 10);
+    ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 102, 30).with_display_quantity(5));
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 7);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 3);
+
+    assert_eq!(ob.get_available_liquidity(Side::Buy, 100), 0);
+    assert_eq!(ob.get_available_liquidity(Side::Buy, 101), 10);
+    assert_eq!(ob.get_available_liquidity(Side::Buy, 110), 40);
+    assert_eq!(ob.get_available_liquidity(Side::Sell, 99), 10);
+
+    // Depth follows partial fills and removals level by level
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 102, 12);
+    let asks = ob.get_depth(Side::Sell);
+    assert!(asks.get_level(101).is_none());
+    assert_eq!(asks.get_level(102).map(|level| (level.quantity, level.count)), Some((28, 1)));
+    assert_eq!(ob.get_depth(Side::Buy).get_level(99).map(|level| (level.quantity, level.count)), Some((10, 2)));
+}
+
+#[test]
+fn test_post_only_rests_when_not_crossing() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
+    let result = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 104, 5).with_post_only(PostOnly::Reject));
+
+    assert_eq!(result.status, OrderStatus::Accepted);
+    assert_eq!(ob.get_best_bid(), Some(104));
+}
+
+#[test]
+fn test_post_only_reject_mode() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
+    let result = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 5).with_post_only(PostOnly::Reject));
+
+    assert_eq!(result.status, OrderStatus::RejectedPostOnlyWouldCross);
+    assert!(result.trades.is_empty());
+    assert_eq!(ob.size(), 1);
+    assert_eq!(ob.get_order_infos().asks[0].quantity, 10);
+}
+
+#[test]
+fn test_post_only_slide_mode() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+
+    let buy = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 107, 5).with_post_only(PostOnly::Slide));
+    assert_eq!(buy.status, OrderStatus::Repriced);
+    assert!(buy.trades.is_empty());
+    assert_eq!(ob.get_order(buy.order_id).unwrap().get_price(), 104);
+    assert_eq!(ob.get_best_bid(), Some(104));
+
+    let sell = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 90, 5).with_post_only(PostOnly::Slide));
+    assert_eq!(sell.status, OrderStatus::Repriced);
+    assert_eq!(ob.get_order(sell.order_id).unwrap().get_price(), 105);
+    assert_eq!(ob.get_order_infos().asks[0].quantity, 15);
+}
+
+#[test]
+fn test_post_only_slide_without_room_is_rejected() {
+    let mut ob = OrderBook::new();
+
+    // There is no valid price below the lowest tick
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 1, 10);
+    let result = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 5, 5).with_post_only(PostOnly::Slide));
+
+    assert_eq!(result.status, OrderStatus::RejectedPostOnlyWouldCross);
+    assert_eq!(ob.get_best_bid(), None);
+}
+
+#[test]
+fn test_pegged_order_without_reference_is_rejected() {
+    let mut ob = OrderBook::new();
+
+    let result = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 0, 10).with_peg(Peg::new(PegType::Primary)));
+    assert_eq!(result.status, OrderStatus::RejectedNoPegReference);
+    assert_eq!(ob.size(), 0);
+}
+
+#[test]
+fn test_primary_peg_follows_best_bid() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 110, 10);
+    let pegged = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 0, 5).with_peg(Peg::new(PegType::Primary)));
+    assert_eq!(pegged.status, OrderStatus::Accepted);
+    assert_eq!(ob.get_order(pegged.order_id).unwrap().get_price(), 100);
+
+    // A better bid moves the peg up after add_order
+    let (better_bid, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 102, 10);
+    assert_eq!(ob.get_order(pegged.order_id).unwrap().get_price(), 102);
+
+    // Cancelling it moves the peg back after cancel_order
+    assert!(ob.cancel_order(better_bid).is_ok());
+    assert_eq!(ob.get_order(pegged.order_id).unwrap().get_price(), 100);
+
+    // Filling the reference bid moves the peg after match_orders
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 98, 10);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    assert_eq!(ob.get_order(pegged.order_id).unwrap().get_price(), 98);
+}
+
+#[test]
+fn test_pegged_orders_do_not_set_the_reference() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 110, 10);
+    let pegged = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 0, 5)
+        .with_peg(Peg::new(PegType::Primary).with_offset(1)));
+
+    // The peg improves on the best bid without chasing its own price
+    assert_eq!(ob.get_order(pegged.order_id).unwrap().get_price(), 101);
+    assert_eq!(ob.get_best_bid(), Some(101));
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 90, 10);
+    assert_eq!(ob.get_order(pegged.order_id).unwrap().get_price(), 101);
+}
+
+#[test]
+fn test_market_peg_never_crosses() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let (ask, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
+    let pegged = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 0, 5).with_peg(Peg::new(PegType::Market)));
+
+    assert!(pegged.trades.is_empty());
+    assert_eq!(ob.get_order(pegged.order_id).unwrap().get_price(), 104);
+
+    // Without a reference ask the peg stays put
+    assert!(ob.cancel_order(ask).is_ok());
+    assert_eq!(ob.get_order(pegged.order_id).unwrap().get_price(), 104);
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 108, 10);
+    assert_eq!(ob.get_order(pegged.order_id).unwrap().get_price(), 107);
+}
+
+#[test]
+fn test_midpoint_peg_with_cap() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let (ask, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 110, 10);
+    let pegged = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 0, 5)
+        .with_peg(Peg::new(PegType::Midpoint).with_cap(104)));
+    assert_eq!(ob.get_order(pegged.order_id).unwrap().get_price(), 105);
+
+    // The midpoint drops to 102 but the cap holds the ask at 104
+    assert!(ob.cancel_order(ask).is_ok());
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 104, 10);
+    assert_eq!(ob.get_order(pegged.order_id).unwrap().get_price(), 104);
+}
+
+#[test]
+fn test_repriced_peg_loses_priority() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 110, 10);
+    let pegged = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 0, 5).with_peg(Peg::new(PegType::Primary)));
+    let (reference, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 10);
+    assert_eq!(level_order_ids(&ob, Side::Buy, 101), vec![reference, pegged.order_id]);
+
+    // Unchanged peg keeps its place when the book changes elsewhere
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 10);
+    assert_eq!(level_order_ids(&ob, Side::Buy, 101), vec![reference, pegged.order_id]);
+    assert_eq!(ob.get_order_infos().bids[0].quantity, 15);
+}
+
+fn trade_at(ob: &mut OrderBook, price: Price) {
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, price, 1);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, price, 1);
+}
+
+#[test]
+fn test_trailing_sell_stop_ratchets_and_triggers() {
+    let mut ob = OrderBook::new();
+
+    trade_at(&mut ob, 100);
+    let stop = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 3)
+        .with_trailing_stop(TrailAmount::Absolute(5)));
+    assert_eq!(stop.status, OrderStatus::PendingTrigger);
+    assert_eq!(ob.stop_book.get(stop.order_id).unwrap().get_stop_price(), Some(95));
+
+    // Rising trades ratchet the stop up
+    trade_at(&mut ob, 110);
+    assert_eq!(ob.stop_book.get(stop.order_id).unwrap().get_stop_price(), Some(105));
+
+    // Falling trades leave it where it is
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 90, 10);
+    trade_at(&mut ob, 107);
+    assert_eq!(ob.stop_book.get(stop.order_id).unwrap().get_stop_price(), Some(105));
+
+    // A trade at the stop releases it as a market order
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 1);
+    let (_, trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 1);
+    assert_eq!(trades.len(), 2);
+    assert_eq!(trades[1].ask_info.order_id, stop.order_id);
+    assert_eq!(trades[1].bid_info.price, 90);
+    assert_eq!(trades[1].ask_info.quantity, 3);
+    assert!(ob.stop_book.is_empty());
+}
+
+#[test]
+fn test_trailing_buy_stop_in_basis_points() {
+    let mut ob = OrderBook::new();
+
+    trade_at(&mut ob, 100);
+    let stop = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 2)
+        .with_trailing_stop(TrailAmount::BasisPoints(1_000)));
+    assert_eq!(ob.stop_book.get(stop.order_id).unwrap().get_stop_price(), Some(110));
+
+    trade_at(&mut ob, 80);
+    assert_eq!(ob.stop_book.get(stop.order_id).unwrap().get_stop_price(), Some(88));
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 95, 10);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 88, 1);
+    let (_, trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 88, 1);
+    assert_eq!(trades.len(), 2);
+    assert_eq!(trades[1].bid_info.order_id, stop.order_id);
+    assert_eq!(trades[1].ask_info.price, 95);
+}
+
+#[test]
+fn test_trailing_stop_keeps_at_least_one_tick() {
+    let mut ob = OrderBook::new();
+    ob.set_instrument_rules(InstrumentRules::new().with_tick_size(5));
+
+    trade_at(&mut ob, 100);
+    // 10 basis points of 100 rounds to nothing, so the stop trails by a tick
+    let stop = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 1)
+        .with_trailing_stop(TrailAmount::BasisPoints(10)));
+    assert_eq!(ob.stop_book.get(stop.order_id).unwrap().get_stop_price(), Some(95));
+
+    // Another print at the same price does not trigger it
+    trade_at(&mut ob, 100);
+    assert!(ob.stop_book.contains(stop.order_id));
+    trade_at(&mut ob, 110);
+    assert_eq!(ob.stop_book.get(stop.order_id).unwrap().get_stop_price(), Some(105));
+}
+
+#[test]
+fn test_trailing_stop_validation() {
+    let mut ob = OrderBook::new();
+
+    let zero = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 3)
+        .with_trailing_stop(TrailAmount::Absolute(0)));
+    assert_eq!(zero.status, OrderStatus::RejectedInvalidTrailAmount);
+
+    // Without a last trade the trailing stop needs a starting stop price
+    let missing = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 3)
+        .with_trailing_stop(TrailAmount::Absolute(5)));
+    assert_eq!(missing.status, OrderStatus::RejectedMissingStopPrice);
+
+    let seeded = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 3)
+        .with_stop_price(90)
+        .with_trailing_stop(TrailAmount::Absolute(5)));
+    assert_eq!(seeded.status, OrderStatus::PendingTrigger);
+    assert_eq!(ob.stop_book.get(seeded.order_id).unwrap().get_stop_price(), Some(90));
+}
+
+#[test]
+fn test_market_fill_and_kill_cancels_remainder() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 5);
+
+    let result = ob.add_order_with_status(Side::Buy, OrdType::Market, TimeInForce::FillAndKill, 0, 20);
+    assert_eq!(result.status, OrderStatus::Executed);
+    assert_eq!(result.trades.len(), 2);
+    assert_eq!(ob.size(), 0);
+    assert_eq!(ob.get_best_bid(), None);
+}
+
+#[test]
+fn test_market_to_limit_rests_at_best_price() {
+    let mut ob = OrderBook::new();
+
+    let empty = ob.add_order_with_status(Side::Buy, OrdType::MarketToLimit, TimeInForce::GoodTillCancel, 0, 8);
+    assert_eq!(empty.status, OrderStatus::RejectedNoLiquidity);
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 5);
+
+    // Only the best level is taken, the remainder rests there as a limit order
+    let result = ob.add_order_with_status(Side::Buy, OrdType::MarketToLimit, TimeInForce::GoodTillCancel, 0, 8);
+    assert_eq!(result.status, OrderStatus::Executed);
+    assert_eq!(result.trades.len(), 1);
+    assert_eq!(result.trades[0].ask_info.price, 100);
+    assert_eq!(ob.get_best_bid(), Some(100));
+    assert_eq!(ob.get_best_ask(), Some(101));
+    assert_eq!(ob.get_order(result.order_id).unwrap().get_ord_type(), OrdType::Limit);
+    assert_eq!(ob.get_order(result.order_id).unwrap().get_remaining_quantity(), 3);
+}
+
+#[test]
+fn test_good_for_day_market_to_limit() {
+    let clock = ManualClock::new(9 * HOUR);
+    let mut ob = OrderBook::with_clock(Box::new(clock.clone()));
+    ob.set_market_schedule(MarketSchedule::daily_close(16, 0));
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+    let (order_id, trades) = ob.add_order(Side::Buy, OrdType::MarketToLimit, TimeInForce::GoodForDay, 0, 8);
+    assert_eq!(trades.len(), 1);
+    assert_eq!(ob.get_order(order_id).unwrap().get_time_in_force(), TimeInForce::GoodForDay);
+
+    clock.set(16 * HOUR);
+    let cancelled = ob.prune_good_for_day_orders();
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0].order_id, order_id);
+    assert_eq!(cancelled[0].price, 100);
+    assert_eq!(cancelled[0].remaining_quantity, 3);
+}
+
+#[test]
+fn test_fill_or_kill_stop_limit() {
+    let mut ob = OrderBook::new();
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 3);
+    let thin = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::StopLimit, TimeInForce::FillOrKill, 101, 5).with_stop_price(100));
+    assert_eq!(thin.status, OrderStatus::PendingTrigger);
+
+    // Triggered, but the book cannot fill it completely so it is killed
+    trade_at(&mut ob, 100);
+    assert!(ob.stop_book.is_empty());
+    assert!(ob.get_order(thin.order_id).is_none());
+    assert_eq!(ob.get_order_infos().asks[0].quantity, 3);
+    match ob.drain_events().as_slice() {
+        [BookEvent::OrderCancelled(cancelled)] => {
+            assert_eq!(cancelled.order_id, thin.order_id);
+            assert_eq!(cancelled.remaining_quantity, 5);
+            assert_eq!(cancelled.reason, CancelReason::StopRejected(OrderStatus::RejectedFillOrKillPartialFill));
+        }
+        events => panic!("unexpected events {:?}", events),
+    }
+
+    let full = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::StopLimit, TimeInForce::FillOrKill, 101, 3).with_stop_price(100));
+    let (_, trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 1);
+    assert!(trades.is_empty());
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 1);
+    assert!(ob.get_order(full.order_id).is_none());
+    assert_eq!(ob.get_best_ask(), None);
+}
+
+#[test]
+fn test_stop_orders_expire() {
+    let clock = ManualClock::new(9 * HOUR);
+    let mut ob = OrderBook::with_clock(Box::new(clock.clone()));
+    ob.set_market_schedule(MarketSchedule::daily_close(16, 0));
+
+    let gtd = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Stop, TimeInForce::GoodTillDate, 0, 5)
+        .with_stop_price(110)
+        .with_expiry(12 * HOUR));
+    let gfd = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::StopLimit, TimeInForce::GoodForDay, 89, 5).with_stop_price(90));
+    assert_eq!(ob.stop_book.len(), 2);
+
+    clock.set(12 * HOUR);
+    let expired = ob.expire_orders();
+    assert_eq!(expired.len(), 1);
+    assert_eq!(expired[0].order_id, gtd.order_id);
+    assert_eq!(expired[0].reason, CancelReason::Expired);
+
+    clock.set(16 * HOUR);
+    let closed = ob.prune_good_for_day_orders();
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].order_id, gfd.order_id);
+    assert!(ob.stop_book.is_empty());
+}
+
+#[test]
+fn test_invalid_combinations_are_rejected() {
+    let mut ob = OrderBook::new();
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+
+    let post_only_market = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Market, TimeInForce::GoodTillCancel, 0, 5)
+        .with_post_only(PostOnly::Reject));
+    assert_eq!(post_only_market.status, OrderStatus::RejectedInvalidCombination);
+
+    let post_only_fak = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::FillAndKill, 105, 5)
+        .with_post_only(PostOnly::Reject));
+    assert_eq!(post_only_fak.status, OrderStatus::RejectedInvalidCombination);
+
+    let pegged_fok = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::FillOrKill, 0, 5)
+        .with_peg(Peg::new(PegType::Primary)));
+    assert_eq!(pegged_fok.status, OrderStatus::RejectedInvalidCombination);
+
+    let trailing_limit = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 5)
+        .with_trailing_stop(TrailAmount::Absolute(5)));
+    assert_eq!(trailing_limit.status, OrderStatus::RejectedInvalidCombination);
+
+    assert_eq!(ob.size(), 1);
+}
+
+#[test]
+fn test_pro_rata_matching() {
+    let mut ob = OrderBook::new();
+    ob.set_matching_policy(Box::new(ProRata));
+
+    let (small, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let (large, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 30);
+
+    let (_, trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 20);
+    assert_eq!(trades.len(), 2);
+    assert_eq!((trades[0].ask_info.order_id, trades[0].ask_info.quantity), (small, 5));
+    assert_eq!((trades[1].ask_info.order_id, trades[1].ask_info.quantity), (large, 15));
+    assert_eq!(ob.get_order_infos().asks[0].quantity, 20);
+
+    // The rest sweeps the level and carries on to the next one
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 10);
+    let (_, trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 25);
+    assert_eq!(trades.len(), 3);
+    assert_eq!(trades[2].ask_info.price, 101);
+    assert_eq!(trades[2].ask_info.quantity, 5);
+    assert_eq!(ob.size(), 1);
+}
+
+#[test]
+fn test_pro_rata_top_order_matching() {
+    let mut ob = OrderBook::new();
+    ob.set_matching_policy(Box::new(ProRataTopOrder));
+
+    let (top, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 20);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 20);
+
+    let (_, trades) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 30);
+    assert_eq!(trades.len(), 3);
+    assert_eq!((trades[0].bid_info.order_id, trades[0].bid_info.quantity), (top, 10));
+    assert_eq!(trades[1].bid_info.quantity, 10);
+    assert_eq!(trades[2].bid_info.quantity, 10);
+    assert!(ob.get_order(top).is_none());
+}
+
+#[test]
+fn test_auction_accumulates_then_uncrosses_at_one_price() {
+    let mut ob = OrderBook::new();
+    ob.open_auction();
+    assert!(ob.is_auction());
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 102, 10);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 10);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 15);
+    let (_, trades) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 10);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 103, 10);
+
+    // Crossed, but nothing trades while the auction is open
+    assert!(trades.is_empty());
+    assert_eq!(ob.size(), 6);
+    assert_eq!(ob.get_best_bid(), Some(102));
+    assert_eq!(ob.get_best_ask(), Some(99));
+
+    let indicative = ob.get_indicative_uncross().unwrap();
+    assert_eq!(indicative.price, 101);
+    assert_eq!(indicative.matched_quantity, 20);
+    assert_eq!(indicative.imbalance_quantity, 5);
+    assert_eq!(indicative.imbalance_side, Some(Side::Sell));
+
+    let trades = ob.uncross_auction();
+    assert!(!ob.is_auction());
+    assert_eq!(ob.get_indicative_uncross(), None);
+    assert_eq!(trades.iter().map(|trade| trade.bid_info.quantity).sum::<Quantity>(), 20);
+    assert!(trades.iter().all(|trade| trade.bid_info.price == 101 && trade.ask_info.price == 101));
+    assert_eq!(ob.get_last_trade_price(), Some(101));
+
+    assert_eq!(ob.get_best_bid(), Some(100));
+    assert_eq!(ob.get_best_ask(), Some(101));
+    assert_eq!(ob.get_order_infos().asks[0].quantity, 5);
+    assert_eq!(ob.size(), 3);
+
+    // Continuous matching resumes
+    let (_, trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 5);
+    assert_eq!(trades.len(), 1);
+}
+
+/// Auction, market orders, stops, icebergs and cancels, returning every
+/// trade and the book left behind
+fn run_ladder_scenario(ob: &mut OrderBook) -> (Vec<Trade>, OrderbookLevelInfos) {
+    let mut trades: Vec<Trade> = Vec::new();
+    ob.open_auction();
+    ob.add_order(Side::Buy, OrdType::Market, TimeInForce::GoodTillCancel, 0, 5);
+    ob.add_order(Side::Sell, OrdType::Market, TimeInForce::GoodTillCancel, 0, 3);
+    for (side, price, quantity) in [(Side::Buy, 102, 10), (Side::Buy, 100, 10), (Side::Sell, 99, 15), (Side::Sell, 103, 10), (Side::Sell, 110, 7)] {
+        ob.add_order(side, OrdType::Limit, TimeInForce::GoodTillCancel, price, quantity);
+    }
+    trades.extend(ob.uncross_auction());
+
+    ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 4).with_stop_price(103));
+    ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 104, 30).with_display_quantity(5));
+    let (cancelled, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 98, 8);
+    trades.extend(ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 104, 12).1);
+    trades.extend(ob.add_order(Side::Sell, OrdType::Market, TimeInForce::GoodTillCancel, 0, 9).1);
+    assert!(ob.cancel_order(cancelled).is_ok());
+    trades.extend(ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::FillOrKill, 110, 20).1);
+    (trades, ob.get_order_infos())
+}
+
+#[test]
+fn test_array_ladder_book_behaves_like_tree_book() {
+    let mut tree = OrderBook::new();
+    let mut array = OrderBook::new();
+    array.set_price_ladder(ArrayLadder::new(90, 120, 1));
+
+    let (tree_trades, tree_infos) = run_ladder_scenario(&mut tree);
+    let (array_trades, array_infos) = run_ladder_scenario(&mut array);
+    assert!(!tree_trades.is_empty());
+    assert_eq!(array_trades, tree_trades);
+    assert_eq!(array_infos, tree_infos);
+    assert_eq!(array.get_best_bid(), tree.get_best_bid());
+    assert_eq!(array.get_best_ask(), tree.get_best_ask());
+}
+
+#[test]
+fn test_set_price_ladder_keeps_resting_orders() {
+    let mut ob = OrderBook::new();
+    let (first, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let (second, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
+
+    ob.set_price_ladder(ArrayLadder::new(50, 150, 1));
+    assert_eq!(level_order_ids(&ob, Side::Buy, 100), vec![first, second]);
+    assert_eq!(ob.get_best_ask(), Some(105));
+
+    let (_, trades) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 12);
+    assert_eq!(trades.len(), 2);
+    assert_eq!(level_order_ids(&ob, Side::Buy, 100), vec![second]);
+}
+
+#[test]
+fn test_auction_market_orders() {
+    let mut ob = OrderBook::new();
+    ob.open_auction();
+
+    let fak = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::FillAndKill, 100, 5);
+    assert_eq!(fak.status, OrderStatus::RejectedImmediateOrderInAuction);
+
+    let (market, _) = ob.add_order(Side::Buy, OrdType::Market, TimeInForce::GoodTillCancel, 0, 5);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 3);
+
+    let trades = ob.uncross_auction();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].bid_info.order_id, market);
+    assert_eq!(trades[0].bid_info.price, 100);
+
+    // The unfilled market quantity rests as a limit at the auction price
+    assert_eq!(ob.get_order(market).unwrap().get_ord_type(), OrdType::Limit);
+    assert_eq!(ob.get_order(market).unwrap().get_remaining_quantity(), 2);
+    assert_eq!(ob.get_best_bid(), Some(100));
+}
+
+#[test]
+fn test_auction_without_cross_cancels_market_orders() {
+    let mut ob = OrderBook::new();
+    ob.open_auction();
+
+    ob.add_order(Side::Buy, OrdType::Market, TimeInForce::GoodTillCancel, 0, 5);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 3);
+    assert!(ob.cancel_order(1).is_ok());
+    assert_eq!(ob.get_indicative_uncross(), None);
+
+    assert!(ob.uncross_auction().is_empty());
+    assert_eq!(ob.size(), 0);
+    match ob.drain_events().as_slice() {
+        [BookEvent::OrderCancelled(cancelled)] => {
+            assert_eq!(cancelled.side, Side::Buy);
+            assert_eq!(cancelled.remaining_quantity, 5);
+            assert_eq!(cancelled.reason, CancelReason::AuctionUnfilled);
+        }
+        events => panic!("unexpected events {:?}", events),
+    }
+}
+
+#[test]
+fn test_trading_session_lifecycle() {
+    let clock = ManualClock::new(8 * HOUR);
+    let mut ob = OrderBook::with_clock(Box::new(clock.clone()));
+    assert_eq!(ob.get_trading_phase(), TradingPhase::Open);
+
+    assert!(ob.set_trading_phase(TradingPhase::Closed).is_some());
+    let closed = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    assert_eq!(closed.status, OrderStatus::RejectedMarketClosed);
+    assert_eq!(ob.set_trading_phase(TradingPhase::Halted), None);
+
+    // Pre-open accumulates without matching
+    clock.set(9 * HOUR);
+    ob.set_trading_phase(TradingPhase::PreOpen);
+    assert!(ob.is_auction());
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 10);
+    let (_, trades) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 4);
+    assert!(trades.is_empty());
+
+    // Opening uncrosses the auction
+    clock.set(10 * HOUR);
+    let trades = ob.set_trading_phase(TradingPhase::Open).unwrap();
+    assert_eq!(trades.len(), 1);
+    assert!(!ob.is_auction());
+    let (_, trades) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 2);
+    assert_eq!(trades.len(), 1);
+
+    let events = ob.drain_events();
+    let phases: Vec<(TradingPhase, TradingPhase, u64)> = events.iter()
+        .map(|event| match event {
+            BookEvent::PhaseChanged(change) => (change.from, change.to, change.timestamp),
+            _ => panic!("unexpected event {:?}", event),
+        })
+        .collect();
+    assert_eq!(phases, vec![
+        (TradingPhase::Open, TradingPhase::Closed, 8 * HOUR),
+        (TradingPhase::Closed, TradingPhase::PreOpen, 9 * HOUR),
+        (TradingPhase::PreOpen, TradingPhase::Open, 10 * HOUR),
+    ]);
+    assert!(ob.drain_events().is_empty());
+}
+
+#[test]
+fn test_halted_book_only_accepts_cancels() {
+    let mut ob = OrderBook::new();
+    let (bid, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let (ask, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
+
+    ob.set_trading_phase(TradingPhase::Halted);
+    let rejected = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
+    assert_eq!(rejected.status, OrderStatus::RejectedTradingHalted);
+
+    let result = ob.modify_order(OrderModify::new(bid, Side::Buy, 105, 10));
+    assert_eq!(result.status, OrderStatus::RejectedTradingHalted);
+    assert!(result.trades.is_empty());
+    assert_eq!(ob.get_order(bid).unwrap().get_price(), 100);
+
+    assert!(ob.cancel_order(ask).is_ok());
+    assert_eq!(ob.size(), 1);
+
+    // Nothing at all is accepted once closed
+    ob.set_trading_phase(TradingPhase::Closed);
+    assert_eq!(ob.cancel_order(bid), Err(CancelRejectReason::MarketClosed));
+    assert_eq!(ob.size(), 1);
+}
+
+#[test]
+fn test_price_band_breach_halts_trading() {
+    let mut ob = OrderBook::new();
+    trade_at(&mut ob, 100);
+    ob.set_price_band(PriceBand::new(500, BreachAction::Halt));
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 103, 5);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 106, 5);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 110, 5);
+
+    // The market order stops at the band instead of sweeping the book
+    let result = ob.add_order_with_status(Side::Buy, OrdType::Market, TimeInForce::GoodTillCancel, 0, 15);
+    assert_eq!(result.trades.len(), 1);
+    assert_eq!(result.trades[0].ask_info.price, 103);
+    assert_eq!(ob.get_trading_phase(), TradingPhase::Halted);
+
+    let events = ob.drain_events();
+    assert_eq!(events.len(), 3);
+    match &events[0] {
+        BookEvent::TradingPaused(paused) => {
+            assert_eq!(paused.order_id, result.order_id);
+            assert_eq!(paused.price, 106);
+            assert_eq!(paused.reference_price, 100);
+            assert_eq!(paused.action, BreachAction::Halt);
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+    assert!(matches!(&events[1], BookEvent::PhaseChanged(change) if change.to == TradingPhase::Halted));
+    // The rest of the market order is cancelled rather than left crossed
+    match &events[2] {
+        BookEvent::OrderCancelled(cancelled) => {
+            assert_eq!(cancelled.order_id, result.order_id);
+            assert_eq!(cancelled.remaining_quantity, 10);
+            assert_eq!(cancelled.reason, CancelReason::PriceBandBreach);
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+    assert!(ob.get_order(result.order_id).is_none());
+
+    let rejected = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 106, 1);
+    assert_eq!(rejected.status, OrderStatus::RejectedTradingHalted);
+
+    // Nothing is left crossed, so reopening does not trade through the band
+    assert!(ob.set_trading_phase(TradingPhase::Open).unwrap().is_empty());
+    assert_eq!(ob.get_best_ask(), Some(106));
+    assert_eq!(ob.size(), 2);
+}
+
+#[test]
+fn test_price_band_breach_starts_volatility_auction() {
+    let mut ob = OrderBook::new();
+    ob.set_price_band(PriceBand::new(500, BreachAction::VolatilityAuction).with_reference_price(100));
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 90, 5);
+    let (sell, trades) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 90, 5);
+    assert!(trades.is_empty());
+    assert!(ob.is_auction());
+    assert_eq!(ob.get_trading_phase(), TradingPhase::Open);
+    assert!(matches!(
+        ob.drain_events().as_slice(),
+        [BookEvent::TradingPaused(paused), BookEvent::OrderCancelled(cancelled)]
+            if paused.order_id == sell && paused.price == 90 && cancelled.order_id == sell
+    ));
+
+    // Orders collect in the auction until it is uncrossed
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 97, 5);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 98, 5);
+    assert_eq!(ob.get_indicative_uncross().unwrap().matched_quantity, 5);
+
+    let trades = ob.uncross_auction();
+    assert_eq!(trades.len(), 1);
+    assert!(!ob.is_auction());
+    assert_eq!(ob.get_best_bid(), Some(90));
+}
+
+#[test]
+fn test_price_band_breach_cancels_the_rest_of_the_order() {
+    let mut ob = OrderBook::new();
+    ob.set_price_band(PriceBand::new(500, BreachAction::VolatilityAuction).with_reference_price(100));
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 150, 5);
+    let (buy, trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 150, 10);
+    assert_eq!(trades.len(), 1);
+    assert!(ob.is_auction());
+    assert!(ob.get_order(buy).is_none());
+
+    // The auction has nothing crossed to trade through the band
+    assert_eq!(ob.get_indicative_uncross(), None);
+    assert!(ob.uncross_auction().is_empty());
+    assert_eq!(ob.get_best_ask(), Some(150));
+}
+
+#[test]
+fn test_uncross_outside_price_band_pauses_again() {
+    let mut ob = OrderBook::new();
+    ob.set_price_band(PriceBand::new(500, BreachAction::VolatilityAuction).with_reference_price(100));
+    ob.open_auction();
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 120, 5);
+    let (buy, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 120, 5);
+    assert_eq!(ob.get_indicative_uncross().unwrap().price, 120);
+
+    assert!(ob.uncross_auction().is_empty());
+    assert!(ob.is_auction());
+    assert_eq!(ob.size(), 2);
+    assert!(matches!(ob.drain_events().as_slice(), [BookEvent::TradingPaused(paused)] if paused.order_id == buy && paused.price == 120));
+
+    // Once the crossing orders are back inside the band the auction uncrosses
+    assert!(ob.cancel_order(buy).is_ok());
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 104, 5);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 104, 5);
+    let trades = ob.uncross_auction();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].bid_info.price, 104);
+    assert!(!ob.is_auction());
+}
+
+#[test]
+fn test_fill_or_kill_respects_price_band() {
+    let mut ob = OrderBook::new();
+    trade_at(&mut ob, 100);
+    ob.set_price_band(PriceBand::new(500, BreachAction::Halt));
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 103, 5);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 106, 5);
+
+    let result = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::FillOrKill, 110, 10);
+    assert_eq!(result.status, OrderStatus::RejectedFillOrKillPartialFill);
+    assert_eq!(ob.get_trading_phase(), TradingPhase::Open);
+    assert!(ob.drain_events().is_empty());
+
+    let result = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::FillOrKill, 110, 5);
+    assert_eq!(result.status, OrderStatus::Executed);
+}
+
+#[test]
+fn test_instrument_rules_reject_orders() {
+    let mut ob = OrderBook::new();
+
+    let zero = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 0);
+    assert_eq!(zero.status, OrderStatus::RejectedInvalidQuantity);
+    let free = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 0, 10);
+    assert_eq!(free.status, OrderStatus::RejectedInvalidPrice);
+
+    ob.set_instrument_rules(InstrumentRules::new()
+        .with_tick_table(TickTable::new(vec![(0, 1), (100, 5)]))
+        .with_lot_size(10)
+        .with_max_quantity(100));
+    assert_eq!(ob.get_instrument_rules().lot_size, 10);
+
+    let off_tick = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 102, 10);
+    assert_eq!(off_tick.status, OrderStatus::RejectedPriceNotOnTick);
+    let off_lot = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 15);
+    assert_eq!(off_lot.status, OrderStatus::RejectedQuantityNotOnLot);
+    let too_big = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 110);
+    assert_eq!(too_big.status, OrderStatus::RejectedAboveMaximumQuantity);
+    let below_band = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 97, 10);
+    assert_eq!(below_band.status, OrderStatus::Accepted);
+
+    assert_eq!(ob.size(), 1);
+}
+
+#[test]
+fn test_negative_prices() {
+    let mut ob = OrderBook::new();
+    ob.set_instrument_rules(InstrumentRules::new()
+        .with_price_scale(PriceScale::new(2))
+        .with_price_range(-10_000, 10_000));
+    let rules = ob.get_instrument_rules().clone();
+
+    let bid_price: Price = rules.parse_price("-2.50").unwrap();
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, bid_price, 10);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, -300, 10);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, -100, 10);
+    assert_eq!(ob.get_best_bid(), Some(-250));
+    assert_eq!(ob.get_best_ask(), Some(-100));
+    assert_eq!(ob.get_order_infos().bids[1].price, -300);
+
+    let (_, trades) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, -275, 15);
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].bid_info.price, -250);
+    assert_eq!(rules.format_price(trades[0].ask_info.price), "-2.75");
+    assert_eq!(ob.get_best_ask(), Some(-275));
+
+    let below = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, -10_001, 10);
+    assert_eq!(below.status, OrderStatus::RejectedInvalidPrice);
+}
+
+#[test]
+fn test_book_over_wide_types() {
+    let mut ob: OrderBook<u64, u64> = OrderBook::default();
+    let large: u64 = u64::from(u32::MAX) * 4;
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 10_000_000_000, large);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 10_000_000_001, large);
+    assert_eq!(ob.get_order_infos().asks[0].quantity, large);
+
+    let (_, trades) = ob.add_order(Side::Buy, OrdType::Market, TimeInForce::FillAndKill, 0, large + 10);
+    assert_eq!(trades.len(), 2);
+    assert_eq!(trades[0].ask_info.quantity, large);
+    assert_eq!(trades[1].ask_info.price, 10_000_000_001);
+    assert_eq!(ob.get_last_trade_price(), Some(10_000_000_001));
+    assert_eq!(ob.get_order_infos().asks[0].quantity, large - 10);
+}
+
+#[test]
+fn test_level_quantity_overflow_is_rejected() {
+    let mut ob = OrderBook::new();
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, Quantity::MAX);
+
+    let overflow = ob.add_order_with_status(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 1);
+    assert_eq!(overflow.status, OrderStatus::RejectedQuantityOverflow);
+    let next_level = ob.add_order_with_status(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 1);
+    assert_eq!(next_level.status, OrderStatus::Accepted);
+    assert_eq!(ob.get_order_infos().asks[0].quantity, Quantity::MAX);
+
+    // Once the level trades down there is room again
+    let (_, trades) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    assert_eq!(trades[0].ask_info.quantity, 10);
+    let refill = ob.add_order_with_status(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    assert_eq!(refill.status, OrderStatus::Accepted);
+    assert_eq!(ob.get_order_infos().asks[0].quantity, Quantity::MAX);
+}
+
+#[test]
+fn test_level_quantity_overflow_with_narrow_quantities() {
+    let mut ob: OrderBook<i64, u32> = OrderBook::default();
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, u32::MAX - 5);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+
+    let overflow = ob.add_order_with_status(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 1);
+    assert_eq!(overflow.status, OrderStatus::RejectedQuantityOverflow);
+    assert_eq!(ob.size(), 2);
+    assert_eq!(ob.get_order_infos().bids[0].quantity, u32::MAX);
+}
+
+#[test]
+fn test_book_over_unsigned_prices() {
+    let mut ob: OrderBook<u32, u32> = OrderBook::default();
+    ob.set_instrument_rules(InstrumentRules::new().with_tick_size(5).with_price_range(0, u32::MAX));
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 5, 10);
+    let result = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 5, 5).with_post_only(PostOnly::Slide));
+    assert_eq!(result.status, OrderStatus::Repriced);
+    assert_eq!(ob.get_best_ask(), Some(10));
+
+    // No tick below the lowest unsigned price to slide a bid to
+    assert!(ob.cancel_order(0).is_ok());
+    assert!(ob.cancel_order(1).is_ok());
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 0, 10);
+    let result = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 10, 5).with_post_only(PostOnly::Slide));
+    assert_eq!(result.status, OrderStatus::RejectedPostOnlyWouldCross);
+}
+
+#[test]
+fn test_post_only_slide_uses_tick_size() {
+    let mut ob = OrderBook::new();
+    ob.set_instrument_rules(InstrumentRules::new().with_tick_size(5));
+
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
+    let result = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 110, 5).with_post_only(PostOnly::Slide));
+
+    assert_eq!(result.status, OrderStatus::Repriced);
+    assert_eq!(ob.get_best_bid(), Some(100));
+}
+
+#[test]
+fn test_pegged_price_stays_on_tick() {
+    let mut ob = OrderBook::new();
+    ob.set_instrument_rules(InstrumentRules::new().with_tick_size(5));
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 115, 10);
+
+    let bid = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 0, 5).with_peg(Peg::new(PegType::Midpoint)));
+    let ask = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 0, 5).with_peg(Peg::new(PegType::Midpoint)));
+    assert_eq!(ob.get_order(bid.order_id).unwrap().get_price(), 105);
+    assert_eq!(ob.get_order(ask.order_id).unwrap().get_price(), 110);
+}
+
+#[test]
+fn test_duplicate_client_order_id_is_rejected() {
+    let mut ob = OrderBook::new();
+    let request = |participant, client_order_id| OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10)
+        .with_participant(participant)
+        .with_client_order_id(client_order_id);
+
+    let first = ob.submit_order(request(1, 42));
+    assert_eq!(first.status, OrderStatus::Accepted);
+    assert_eq!(ob.find_client_order(Some(1), 42), Some(first.order_id));
+
+    // Rejected, but still given its own exchange id
+    let duplicate = ob.submit_order(request(1, 42));
+    assert_eq!(duplicate.status, OrderStatus::RejectedDuplicateId);
+    assert_ne!(duplicate.order_id, first.order_id);
+    assert_eq!(ob.size(), 1);
+
+    // Client order ids are scoped per participant
+    assert_eq!(ob.submit_order(request(2, 42)).status, OrderStatus::Accepted);
+    assert_eq!(ob.submit_order(request(1, 43)).status, OrderStatus::Accepted);
+
+    // Free again once the order has left the book
+    assert!(ob.cancel_order(first.order_id).is_ok());
+    assert_eq!(ob.find_client_order(Some(1), 42), None);
+    assert_eq!(ob.submit_order(request(1, 42)).status, OrderStatus::Accepted);
+}
+
+#[test]
+fn test_client_order_id_freed_when_filled() {
+    let mut ob = OrderBook::new();
+    let sell = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10).with_participant(1).with_client_order_id(5));
+    let buy = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10).with_participant(2).with_client_order_id(5));
+
+    assert_eq!(buy.status, OrderStatus::Executed);
+    assert_eq!(ob.find_client_order(Some(1), 5), None);
+    assert_eq!(ob.find_client_order(Some(2), 5), None);
+    assert_ne!(sell.order_id, buy.order_id);
+}
+
+#[test]
+fn test_client_order_id_kept_by_stop_and_peg_moves() {
+    let mut ob = OrderBook::new();
+    let stop = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 5).with_stop_price(110).with_client_order_id(1));
+    assert_eq!(stop.status, OrderStatus::PendingTrigger);
+    let duplicate = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 90, 5).with_client_order_id(1));
+    assert_eq!(duplicate.status, OrderStatus::RejectedDuplicateId);
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let pegged = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 0, 5).with_peg(Peg::new(PegType::Primary)).with_client_order_id(2));
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 10);
+    assert_eq!(ob.get_order(pegged.order_id).unwrap().get_price(), 101);
+    assert_eq!(ob.find_client_order(None, 2), Some(pegged.order_id));
+}
+
+#[test]
+fn test_client_order_history_window() {
+    let mut ob = OrderBook::new();
+    ob.set_client_order_history(2);
+    let request = |client_order_id| OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10)
+        .with_participant(1)
+        .with_client_order_id(client_order_id);
+
+    for client_order_id in 1..=3 {
+        let result = ob.submit_order(request(client_order_id));
+        assert!(ob.cancel_order(result.order_id).is_ok());
+    }
+
+    // Only the last two ids to leave the book are remembered
+    assert_eq!(ob.submit_order(request(3)).status, OrderStatus::RejectedDuplicateId);
+    assert_eq!(ob.submit_order(request(2)).status, OrderStatus::RejectedDuplicateId);
+    assert_eq!(ob.submit_order(request(1)).status, OrderStatus::Accepted);
+
+    // Rejected orders never used their id
+    let rejected = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::FillAndKill, 200, 5).with_participant(1).with_client_order_id(10));
+    assert_eq!(rejected.status, OrderStatus::RejectedFillAndKillNoMatch);
+    assert_eq!(ob.submit_order(request(10)).status, OrderStatus::Accepted);
+}
+
+#[test]
+fn test_trades_carry_participants() {
+    let mut ob = OrderBook::new();
+    ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10).with_participant(1));
+    let result = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 4).with_participant(2));
+
+    assert_eq!(result.trades.len(), 1);
+    assert_eq!(result.trades[0].bid_info.participant, Some(2));
+    assert_eq!(result.trades[0].ask_info.participant, Some(1));
+
+    // Anonymous orders trade without a participant
+    let trades = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 6).1;
+    assert_eq!((trades[0].bid_info.participant, trades[0].ask_info.participant), (None, Some(1)));
+}
+
+#[test]
+fn test_auction_trades_carry_participants() {
+    let mut ob = OrderBook::new();
+    ob.open_auction();
+    ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 10).with_participant(3));
+    ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 10).with_participant(4));
+
+    let trades = ob.uncross_auction();
+    assert_eq!(trades.len(), 1);
+    assert_eq!((trades[0].bid_info.participant, trades[0].ask_info.participant), (Some(3), Some(4)));
+}
+
+#[test]
+fn test_participant_open_orders() {
+    let mut ob = OrderBook::new();
+    let bid = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 10).with_participant(1));
+    let ask = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 10).with_participant(1));
+    let stop = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 5).with_stop_price(110).with_participant(1));
+    ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 102, 10).with_participant(2));
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 103, 10);
+
+    let order_ids = |ob: &OrderBook, participant| ob.get_participant_orders(participant)
+        .iter()
+        .map(|order| order.get_order_id())
+        .collect::<Vec<_>>();
+    assert_eq!(order_ids(&ob, 1), vec![bid.order_id, ask.order_id, stop.order_id]);
+    assert_eq!(ob.get_order(stop.order_id).map(|order| order.get_participant()), Some(Some(1)));
+
+    // Filled and cancelled orders are no longer open
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 10);
+    assert!(ob.cancel_order(stop.order_id).is_ok());
+    assert_eq!(order_ids(&ob, 1), vec![bid.order_id]);
+    assert_eq!(ob.get_participant_orders(2).len(), 1);
+    assert!(ob.get_participant_orders(9).is_empty());
+}
+
+#[test]
+fn test_partial_fill_seen_through_every_view() {
+    let mut ob = OrderBook::new();
+    let resting = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10).with_participant(1)).order_id;
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 4);
+
+    // Lookups by id, by level and by participant all read the same order
+    assert_eq!(ob.get_order(resting).unwrap().get_remaining_quantity(), 6);
+    assert_eq!(ob.get_level_orders(Side::Sell, 100).next().unwrap().get_remaining_quantity(), 6);
+    assert_eq!(ob.get_participant_orders(1)[0].get_remaining_quantity(), 6);
+    assert_eq!(ob.get_order_infos().asks[0].quantity, 6);
+
+    let cancelled = ob.cancel_order(resting).unwrap();
+    assert_eq!(cancelled.remaining_quantity, 6);
+    assert_eq!(cancelled.filled_quantity, 4);
+}
+
+#[test]
+fn test_modify_reducing_quantity_keeps_queue_position() {
+    let mut ob = OrderBook::new();
+    let (first, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let (second, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+
+    let result = ob.modify_order(OrderModify::new(first, Side::Buy, 100, 4));
+    assert_eq!((result.order_id, result.status), (first, OrderStatus::Accepted));
+    assert_eq!(ob.get_order_infos().bids[0].quantity, 14);
+
+    // The reduced order is still first in the queue
+    let trades = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 4).1;
+    assert_eq!(trades[0].bid_info.order_id, first);
+    assert!(ob.get_order(first).is_none());
+    assert_eq!(ob.get_order(second).unwrap().get_remaining_quantity(), 10);
+}
+
+#[test]
+fn test_modify_price_or_increase_loses_priority() {
+    let mut ob = OrderBook::new();
+    let (first, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let (second, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+
+    let result = ob.modify_order(OrderModify::new(first, Side::Buy, 100, 12));
+    assert_eq!((result.order_id, result.status), (first, OrderStatus::Accepted));
+    let trades = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5).1;
+    assert_eq!(trades[0].bid_info.order_id, second);
+
+    // A new price that crosses trades straight away
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 3);
+    let result = ob.modify_order(OrderModify::new(first, Side::Buy, 101, 12));
+    assert_eq!((result.order_id, result.status), (first, OrderStatus::Executed));
+    assert_eq!(result.trades[0].bid_info.quantity, 3);
+    assert_eq!(ob.get_order(first).unwrap().get_price(), 101);
+    assert_eq!(ob.get_order(first).unwrap().get_remaining_quantity(), 9);
+}
+
+#[test]
+fn test_modify_partially_filled_order() {
+    let mut ob = OrderBook::new();
+    let (bid, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 6);
+
+    let result = ob.modify_order(OrderModify::new(bid, Side::Buy, 100, 6));
+    assert_eq!(result.status, OrderStatus::RejectedQuantityBelowFilled);
+
+    let result = ob.modify_order(OrderModify::new(bid, Side::Buy, 100, 8));
+    assert_eq!(result.status, OrderStatus::Accepted);
+    assert_eq!(ob.get_order(bid).unwrap().get_remaining_quantity(), 2);
+    assert_eq!(ob.get_order_infos().bids[0].quantity, 2);
+}
+
+#[test]
+fn test_modify_rejections() {
+    let mut ob = OrderBook::new();
+    ob.set_instrument_rules(InstrumentRules::new().with_tick_size(5));
+    let (bid, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let (ask, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
+
+    assert_eq!(ob.modify_order(OrderModify::new(bid, Side::Sell, 100, 10)).status, OrderStatus::RejectedSideChange);
+    assert_eq!(ob.modify_order(OrderModify::new(99, Side::Buy, 100, 10)).status, OrderStatus::RejectedUnknownOrder);
+    assert_eq!(ob.modify_order(OrderModify::new(bid, Side::Buy, 101, 10)).status, OrderStatus::RejectedPriceNotOnTick);
+
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
+    assert_eq!(ob.modify_order(OrderModify::new(ask, Side::Sell, 105, 5)).status, OrderStatus::RejectedOrderFilled);
+
+    assert!(ob.cancel_order(bid).is_ok());
+    assert_eq!(ob.modify_order(OrderModify::new(bid, Side::Buy, 100, 5)).status, OrderStatus::RejectedUnknownOrder);
+}
+
+#[test]
+fn test_rejected_replace_keeps_original_order() {
+    let mut ob = OrderBook::new();
+    let first = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10).with_post_only(PostOnly::Reject)).order_id;
+    let (second, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
+
+    let result = ob.modify_order(OrderModify::new(first, Side::Buy, 105, 10));
+    assert_eq!(result.status, OrderStatus::RejectedPostOnlyWouldCross);
+    assert_eq!(ob.get_order(first).unwrap().get_price(), 100);
+    assert_eq!(level_order_ids(&ob, Side::Buy, 100), vec![first, second]);
+}
+
+#[test]
+fn test_modify_stop_order() {
+    let mut ob = OrderBook::new();
+    let stop = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::StopLimit, TimeInForce::GoodTillCancel, 110, 5).with_stop_price(105));
+
+    let result = ob.modify_order(OrderModify::new(stop.order_id, Side::Buy, 112, 8));
+    assert_eq!(result.status, OrderStatus::PendingTrigger);
+    let order = ob.get_order(stop.order_id).unwrap();
+    assert_eq!((order.get_price(), order.get_initial_quantity()), (112, 8));
+}
+
+#[test]
+fn test_cancel_returns_cancelled_order() {
+    let mut ob = OrderBook::new();
+    let (bid, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 4);
+
+    let cancelled = ob.cancel_order(bid).unwrap();
+    assert_eq!(cancelled.order.get_order_id(), bid);
+    assert_eq!(cancelled.order.get_price(), 100);
+    assert_eq!((cancelled.remaining_quantity, cancelled.filled_quantity), (6, 4));
+    assert_eq!(ob.size(), 0);
+
+    // Untriggered stops can be cancelled too
+    let stop = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 5).with_stop_price(110));
+    assert_eq!(ob.cancel_order(stop.order_id).map(|cancelled| cancelled.remaining_quantity), Ok(5));
+}
+
+#[test]
+fn test_cancel_reject_reasons() {
+    let mut ob = OrderBook::new();
+    let (ask, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+    let (bid, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+
+    assert_eq!(ob.cancel_order(ask), Err(CancelRejectReason::AlreadyFilled));
+    assert_eq!(ob.cancel_order(bid), Err(CancelRejectReason::AlreadyFilled));
+    assert_eq!(ob.cancel_order(42), Err(CancelRejectReason::UnknownOrder));
+
+    let (resting, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 5);
+    assert!(ob.cancel_order(resting).is_ok());
+    assert_eq!(ob.cancel_order(resting), Err(CancelRejectReason::UnknownOrder));
+    assert_eq!(CancelRejectReason::UnknownOrder.message(), "Cancel rejected: no live order with that ID");
+}
+
+#[test]
+fn test_filled_order_history_is_bounded() {
+    let mut ob = OrderBook::new();
+    ob.set_filled_order_history(2);
+
+    let (first, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+    let (second, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+    let (third, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+    ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 5);
+
+    // Only the last two fills are remembered
+    assert_eq!(ob.cancel_order(first), Err(CancelRejectReason::UnknownOrder));
+    assert_eq!(ob.cancel_order(second), Err(CancelRejectReason::UnknownOrder));
+    assert_eq!(ob.cancel_order(third), Err(CancelRejectReason::AlreadyFilled));
+
+    ob.set_filled_order_history(0);
+    assert_eq!(ob.cancel_order(third), Err(CancelRejectReason::UnknownOrder));
+}
+
+#[test]
+fn test_cancel_checks_owner() {
+    let mut ob = OrderBook::new();
+    let owned = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10).with_participant(1));
+    let (anonymous, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 10);
+
+    assert_eq!(ob.cancel_participant_order(2, owned.order_id), Err(CancelRejectReason::WrongOwner));
+    assert_eq!(ob.cancel_participant_order(2, anonymous), Err(CancelRejectReason::WrongOwner));
+    assert_eq!(ob.cancel_participant_order(2, 42), Err(CancelRejectReason::UnknownOrder));
+    assert!(ob.cancel_participant_order(1, owned.order_id).is_ok());
+    assert_eq!(ob.size(), 1);
+}
+
+#[test]
+fn test_cancel_orders_reports_each_order() {
+    let mut ob = OrderBook::new();
+    let (first, _) = ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+    let (second, _) = ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 105, 10);
+
+    let results = ob.cancel_orders(vec![first, 42, second]);
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().map(|cancelled| cancelled.order.get_order_id()), Ok(first));
+    assert_eq!(results[1], Err(CancelRejectReason::UnknownOrder));
+    assert!(results[2].is_ok());
+
+    ob.set_trading_phase(TradingPhase::Closed);
+    assert_eq!(ob.cancel_orders(vec![first]), vec![Err(CancelRejectReason::MarketClosed)]);
+}
+
+#[test]
+fn test_mass_cancel_by_participant_and_side() {
+    let mut ob = OrderBook::new();
+    let bid = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 10).with_participant(1));
+    let ask = ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 101, 10).with_participant(1));
+    let stop = ob.submit_order(OrderRequest::new(Side::Buy, OrdType::StopLimit, TimeInForce::GoodTillCancel, 110, 5).with_stop_price(105).with_participant(1));
+    ob.submit_order(OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 99, 10).with_participant(2));
+
+    let summary = ob.mass_cancel(MassCancel::new().with_participant(1).with_side(Side::Buy)).unwrap();
+    let order_ids: Vec<_> = summary.cancelled.iter().map(|cancelled| cancelled.order.get_order_id()).collect();
+    assert_eq!(order_ids, vec![bid.order_id, stop.order_id]);
+    assert_eq!((summary.count(), summary.get_cancelled_quantity()), (2, 15));
+
+    assert!(ob.get_order(ask.order_id).is_some());
+    assert_eq!(ob.get_participant_orders(1).len(), 1);
+    assert_eq!(ob.get_order_infos().bids[0].quantity, 10);
+}
+
+#[test]
+fn test_mass_cancel_by_price_range_and_type() {
+    let mut ob = OrderBook::new();
+    for price in [95, 97, 99] {
+        ob.add_order(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, price, 10);
+    }
+    for price in [101, 103] {
+        ob.add_order(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, price, 10);
+    }
+    ob.submit_order(OrderRequest::new(Side::Sell, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 5).with_stop_price(90));
+
+    let summary = ob.mass_cancel(MassCancel::new().with_price_range(97, 101).with_ord_type(OrdType::Limit)).unwrap();
+    assert_eq!(summary.count(), 3);
+    let infos = ob.get_order_infos();
+    assert_eq!(infos.bids.iter().map(|level| level.price).collect::<Vec<_>>(), vec![95]);
+    assert_eq!(infos.asks.iter().map(|level| level.price).collect::<Vec<_>>(), vec![103]);
+    assert_eq!(ob.stop_book.len(), 1);
+
+    // An empty range cancels nothing
+    assert_eq!(ob.mass_cancel(MassCancel::new().with_price_range(103, 95)).unwrap().count(), 0);
+
+    let summary = ob.mass_cancel(MassCancel::new()).unwrap();
+    assert_eq!(summary.count(), 3);
+    assert_eq!((ob.size(), ob.stop_book.len()), (0, 0));
+    assert!(ob.get_order_infos().bids.is_empty());
+
+    ob.set_trading_phase(TradingPhase::Closed);
+    assert_eq!(ob.mass_cancel(MassCancel::new()), Err(CancelRejectReason::MarketClosed));
+}
@@ -0,0 +1,94 @@
+use my_order_book::{OrderRequest, OrdType, Price, Quantity, TimeInForce, Peg, PegType, PostOnly, Side, TrailAmount};
+
+#[test]
+fn test_order_request_creation() {
+    let request: OrderRequest = OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10);
+
+    assert_eq!(request.get_side(), Side::Buy);
+    assert_eq!((request.get_ord_type(), request.get_time_in_force()), (OrdType::Limit, TimeInForce::GoodTillCancel));
+    assert_eq!(request.get_price(), 100);
+    assert_eq!(request.get_quantity(), 10);
+    assert_eq!(request.get_stop_price(), None);
+    assert_eq!(request.get_expiry(), None);
+}
+
+#[test]
+fn test_order_request_with_expiry() {
+    let request: OrderRequest = OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillDate, 100, 10).with_expiry(1_000);
+    let order = request.to_order(7);
+
+    assert_eq!(request.get_expiry(), Some(1_000));
+    assert_eq!(order.get_expiry(), Some(1_000));
+    assert_eq!((order.get_ord_type(), order.get_time_in_force()), (OrdType::Limit, TimeInForce::GoodTillDate));
+}
+
+#[test]
+fn test_order_request_with_stop_price() {
+    let request: OrderRequest = OrderRequest::new(Side::Sell, OrdType::StopLimit, TimeInForce::GoodTillCancel, 95, 10).with_stop_price(97);
+
+    assert_eq!(request.get_stop_price(), Some(97));
+    assert_eq!(request.get_price(), 95);
+}
+
+#[test]
+fn test_order_request_to_order() {
+    let request: OrderRequest = OrderRequest::new(Side::Sell, OrdType::StopLimit, TimeInForce::GoodTillCancel, 95, 10).with_stop_price(97);
+    let order = request.to_order(42);
+
+    assert_eq!(order.get_order_id(), 42);
+    assert_eq!(order.get_side(), Side::Sell);
+    assert_eq!((order.get_ord_type(), order.get_time_in_force()), (OrdType::StopLimit, TimeInForce::GoodTillCancel));
+    assert_eq!(order.get_price(), 95);
+    assert_eq!(order.get_initial_quantity(), 10);
+    assert_eq!(order.get_stop_price(), Some(97));
+    assert_eq!(order.filled_quantity, 0);
+}
+
+#[test]
+fn test_order_request_with_display_quantity() {
+    let request: OrderRequest = OrderRequest::new(Side::Sell, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 50).with_display_quantity(10);
+    let order = request.to_order(3);
+
+    assert_eq!(request.get_display_quantity(), Some(10));
+    assert_eq!(order.get_display_quantity(), Some(10));
+    assert_eq!(order.get_visible_quantity(), 10);
+    assert_eq!(order.get_remaining_quantity(), 50);
+}
+
+#[test]
+fn test_order_request_with_post_only() {
+    let request: OrderRequest = OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10).with_post_only(PostOnly::Slide);
+    let order = request.to_order(1);
+
+    assert_eq!(request.get_post_only(), Some(PostOnly::Slide));
+    assert_eq!(order.get_post_only(), Some(PostOnly::Slide));
+    assert_eq!(OrderRequest::<Price, Quantity>::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10).get_post_only(), None);
+}
+
+#[test]
+fn test_order_request_with_peg() {
+    let peg: Peg = Peg::new(PegType::Midpoint).with_offset(1);
+    let request: OrderRequest = OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 0, 10).with_peg(peg);
+
+    assert_eq!(request.get_peg(), Some(peg));
+    assert_eq!(request.to_order(1).get_peg(), Some(peg));
+}
+
+#[test]
+fn test_order_request_with_trailing_stop() {
+    let request: OrderRequest = OrderRequest::new(Side::Sell, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 10).with_trailing_stop(TrailAmount::BasisPoints(50));
+
+    assert_eq!(request.get_trail(), Some(TrailAmount::BasisPoints(50)));
+    assert_eq!(request.to_order(1).get_trail(), Some(TrailAmount::BasisPoints(50)));
+}
+
+#[test]
+fn test_order_request_with_client_order_id() {
+    let request: OrderRequest = OrderRequest::new(Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10)
+        .with_participant(7)
+        .with_client_order_id(1_001);
+    let order = request.to_order(3);
+
+    assert_eq!((request.get_participant(), request.get_client_order_id()), (Some(7), Some(1_001)));
+    assert_eq!((order.get_participant(), order.get_client_order_id()), (Some(7), Some(1_001)));
+}
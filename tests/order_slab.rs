@@ -0,0 +1,74 @@
+use my_order_book::{Order, OrderHandle, OrderSlab, OrdType, PriceLevel, Side, TimeInForce};
+
+fn order(id: u64) -> Order {
+    Order::new(id, Side::Buy, OrdType::Limit, TimeInForce::GoodTillCancel, 100, 10)
+}
+
+fn level_ids(slab: &OrderSlab, level: &PriceLevel) -> Vec<u64> {
+    slab.iter(level).map(|order: &Order| order.get_order_id()).collect()
+}
+
+#[test]
+fn test_push_back_keeps_time_priority() {
+    let mut slab: OrderSlab = OrderSlab::new();
+    let mut level: PriceLevel = PriceLevel::new();
+    let first: OrderHandle = slab.push_back(&mut level, order(1));
+    let last: OrderHandle = slab.push_back(&mut level, order(2));
+
+    assert_eq!(level_ids(&slab, &level), vec![1, 2]);
+    assert_eq!(level.front(), Some(first));
+    assert_eq!(level.back(), Some(last));
+    assert_eq!(level.len(), 2);
+    assert_eq!(slab.len(), 2);
+}
+
+#[test]
+fn test_remove_from_front_middle_and_back() {
+    let mut slab: OrderSlab = OrderSlab::new();
+    let mut level: PriceLevel = PriceLevel::new();
+    let handles: Vec<OrderHandle> = (1..=4).map(|id: u64| slab.push_back(&mut level, order(id))).collect();
+
+    assert_eq!(slab.remove(&mut level, handles[1]).map(|order: Order| order.get_order_id()), Some(2));
+    assert_eq!(level_ids(&slab, &level), vec![1, 3, 4]);
+    slab.remove(&mut level, handles[0]);
+    slab.remove(&mut level, handles[3]);
+    assert_eq!(level_ids(&slab, &level), vec![3]);
+    assert_eq!(level.front(), level.back());
+
+    slab.remove(&mut level, handles[2]);
+    assert!(level.is_empty());
+    assert!(slab.is_empty());
+    assert!(slab.remove(&mut level, handles[2]).is_none());
+}
+
+#[test]
+fn test_insert_after_and_move_to_back() {
+    let mut slab: OrderSlab = OrderSlab::new();
+    let mut level: PriceLevel = PriceLevel::new();
+    let first: OrderHandle = slab.push_back(&mut level, order(1));
+    slab.push_back(&mut level, order(2));
+
+    slab.insert_after(&mut level, Some(first), order(3));
+    slab.insert_after(&mut level, None, order(4));
+    assert_eq!(level_ids(&slab, &level), vec![4, 1, 3, 2]);
+
+    slab.move_to_back(&mut level, first);
+    assert_eq!(level_ids(&slab, &level), vec![4, 3, 2, 1]);
+    assert_eq!(level.back(), Some(first));
+    assert_eq!(slab.next(first), None);
+    assert_eq!(level.len(), 4);
+}
+
+#[test]
+fn test_freed_slots_are_reused() {
+    let mut slab: OrderSlab = OrderSlab::new();
+    let mut level: PriceLevel = PriceLevel::new();
+    let first: OrderHandle = slab.push_back(&mut level, order(1));
+    slab.push_back(&mut level, order(2));
+
+    slab.remove(&mut level, first);
+    let reused: OrderHandle = slab.push_back(&mut level, order(3));
+    assert_eq!(reused, first);
+    assert_eq!(slab.get(reused).map(|order: &Order| order.get_order_id()), Some(3));
+    assert_eq!(level_ids(&slab, &level), vec![2, 3]);
+}
@@ -0,0 +1,55 @@
+use my_order_book::{Peg, PegType, Price, Side};
+
+#[test]
+fn test_peg_creation() {
+    let peg: Peg = Peg::new(PegType::Primary);
+    assert_eq!(peg.peg_type, PegType::Primary);
+    assert_eq!(peg.offset, 0);
+    assert_eq!(peg.cap, None);
+
+    let peg: Peg = Peg::new(PegType::Market).with_offset(-2).with_cap(105);
+    assert_eq!(peg.offset, -2);
+    assert_eq!(peg.cap, Some(105));
+}
+
+#[test]
+fn test_primary_peg_target() {
+    let peg: Peg = Peg::new(PegType::Primary);
+    assert_eq!(peg.target_price(Side::Buy, Some(100), Some(105)), Some(100));
+    assert_eq!(peg.target_price(Side::Sell, Some(100), Some(105)), Some(105));
+    assert_eq!(peg.target_price(Side::Buy, None, Some(105)), None);
+}
+
+#[test]
+fn test_market_peg_target() {
+    let peg: Peg = Peg::new(PegType::Market).with_offset(-1);
+    assert_eq!(peg.target_price(Side::Buy, Some(100), Some(105)), Some(104));
+    assert_eq!(peg.target_price(Side::Sell, Some(100), Some(105)), Some(99));
+    assert_eq!(peg.target_price(Side::Sell, None, Some(105)), None);
+}
+
+#[test]
+fn test_midpoint_peg_rounds_passively() {
+    let peg: Peg = Peg::new(PegType::Midpoint);
+    assert_eq!(peg.target_price(Side::Buy, Some(100), Some(105)), Some(102));
+    assert_eq!(peg.target_price(Side::Sell, Some(100), Some(105)), Some(103));
+    assert_eq!(peg.target_price(Side::Buy, Some(100), Some(104)), Some(102));
+    assert_eq!(peg.target_price(Side::Buy, Some(100), None), None);
+}
+
+#[test]
+fn test_peg_cap() {
+    let peg: Peg = Peg::new(PegType::Primary).with_offset(2).with_cap(101);
+    assert_eq!(peg.target_price(Side::Buy, Some(100), Some(105)), Some(101));
+    assert_eq!(peg.target_price(Side::Buy, Some(98), Some(105)), Some(100));
+
+    let peg: Peg = Peg::new(PegType::Primary).with_offset(-2).with_cap(104);
+    assert_eq!(peg.target_price(Side::Sell, Some(100), Some(105)), Some(104));
+}
+
+#[test]
+fn test_peg_offset_out_of_range() {
+    let peg: Peg = Peg::new(PegType::Primary).with_offset(-10);
+    assert_eq!(peg.target_price(Side::Buy, Some(5), Some(105)), Some(-5));
+    assert_eq!(peg.target_price(Side::Buy, Some(Price::MIN), Some(105)), None);
+}
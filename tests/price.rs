@@ -0,0 +1,69 @@
+use my_order_book::{Price, PriceScale};
+
+#[test]
+fn test_price_scale_unit() {
+    assert_eq!(PriceScale::default().unit(), 1);
+    assert_eq!(PriceScale::new(2).unit(), 100);
+    assert_eq!(PriceScale::new(40).get_decimals(), PriceScale::MAX_DECIMALS);
+
+    // The widest scale still parses and formats without overflowing
+    let widest = PriceScale::new(40);
+    assert_eq!(widest.unit(), 1_000_000_000_000_000_000);
+    assert_eq!(widest.parse::<Price>("1"), Some(1_000_000_000_000_000_000));
+    assert_eq!(widest.format::<Price>(1_500_000_000_000_000_000), "1.500000000000000000");
+}
+
+#[test]
+fn test_parse_price() {
+    let scale = PriceScale::new(2);
+    assert_eq!(scale.parse::<Price>("12.34"), Some(1_234));
+    assert_eq!(scale.parse::<Price>("12.3"), Some(1_230));
+    assert_eq!(scale.parse::<Price>("12"), Some(1_200));
+    assert_eq!(scale.parse::<Price>(".5"), Some(50));
+    assert_eq!(scale.parse::<Price>("+1.05"), Some(105));
+    assert_eq!(scale.parse::<Price>("-12.34"), Some(-1_234));
+    assert_eq!(scale.parse::<Price>("-0.01"), Some(-1));
+    assert_eq!(scale.parse::<Price>(" 7.00 "), Some(700));
+}
+
+#[test]
+fn test_parse_price_rejects_malformed_input() {
+    let scale = PriceScale::new(2);
+    assert_eq!(scale.parse::<Price>(""), None);
+    assert_eq!(scale.parse::<Price>("-"), None);
+    assert_eq!(scale.parse::<Price>("."), None);
+    assert_eq!(scale.parse::<Price>("1.2.3"), None);
+    assert_eq!(scale.parse::<Price>("1e5"), None);
+    assert_eq!(scale.parse::<Price>("--1"), None);
+    assert_eq!(scale.parse::<Price>("12.345"), None);
+    assert_eq!(PriceScale::default().parse::<Price>("1.5"), None);
+}
+
+#[test]
+fn test_parse_price_out_of_range() {
+    let scale = PriceScale::new(0);
+    assert_eq!(scale.parse::<Price>("9223372036854775807"), Some(Price::MAX));
+    assert_eq!(scale.parse::<Price>("-9223372036854775808"), Some(Price::MIN));
+    assert_eq!(scale.parse::<Price>("9223372036854775808"), None);
+    assert_eq!(PriceScale::new(4).parse::<Price>("922337203685477.5808"), None);
+}
+
+#[test]
+fn test_format_price() {
+    let scale = PriceScale::new(2);
+    assert_eq!(scale.format::<Price>(1_234), "12.34");
+    assert_eq!(scale.format::<Price>(5), "0.05");
+    assert_eq!(scale.format::<Price>(0), "0.00");
+    assert_eq!(scale.format::<Price>(-1_234), "-12.34");
+    assert_eq!(scale.format::<Price>(-1), "-0.01");
+    assert_eq!(PriceScale::default().format::<Price>(-42), "-42");
+    assert_eq!(PriceScale::new(0).format::<Price>(Price::MIN), "-9223372036854775808");
+}
+
+#[test]
+fn test_format_round_trips_through_parse() {
+    let scale = PriceScale::new(3);
+    for price in [0, 1, -1, 999, -1_000, 123_456, Price::MAX, Price::MIN] {
+        assert_eq!(scale.parse::<Price>(&scale.format::<Price>(price)), Some(price));
+    }
+}
@@ -0,0 +1,104 @@
+use my_order_book::{ArrayLadder, Price, PriceLadder, PriceLevel, TreeLadder};
+
+fn prices(ladder: &dyn PriceLadder) -> Vec<Price> {
+    ladder.iter().map(|(price, _): (Price, &PriceLevel)| price).collect()
+}
+
+#[test]
+fn test_array_ladder_finds_best_levels() {
+    let mut ladder: ArrayLadder = ArrayLadder::new(100, 200, 1);
+    assert!(ladder.is_empty());
+    assert_eq!(ladder.min_price(), None);
+
+    for price in [150, 101, 199, 164] {
+        ladder.entry(price);
+    }
+    assert_eq!(ladder.len(), 4);
+    assert_eq!(ladder.min_price(), Some(101));
+    assert_eq!(ladder.max_price(), Some(199));
+
+    assert!(ladder.remove(101).is_some());
+    assert!(ladder.remove(101).is_none());
+    assert!(ladder.get(101).is_none());
+    assert_eq!(ladder.min_price(), Some(150));
+    assert_eq!(prices(&ladder), vec![150, 164, 199]);
+}
+
+#[test]
+fn test_array_ladder_indexes_by_tick() {
+    let mut ladder: ArrayLadder = ArrayLadder::new(1000, 2000, 5);
+    ladder.entry(1005);
+    ladder.entry(1995);
+    // Off the tick grid and out of range prices still have levels
+    ladder.entry(1003);
+    ladder.entry(5);
+    ladder.entry(Price::MAX);
+
+    assert_eq!(prices(&ladder), vec![5, 1003, 1005, 1995, Price::MAX]);
+    assert_eq!(ladder.iter().rev().map(|(price, _)| price).collect::<Vec<_>>(), vec![Price::MAX, 1995, 1005, 1003, 5]);
+    assert_eq!(ladder.range(1001, 1995).map(|(price, _)| price).collect::<Vec<_>>(), vec![1003, 1005, 1995]);
+    assert_eq!(ladder.range(1006, 1994).count(), 0);
+    assert_eq!(ladder.min_price(), Some(5));
+    assert_eq!(ladder.max_price(), Some(Price::MAX));
+}
+
+#[test]
+fn test_array_ladder_searches_across_bitmap_words() {
+    let mut ladder: ArrayLadder = ArrayLadder::new(0, 20_000, 1);
+    ladder.entry(63);
+    ladder.entry(64);
+    ladder.entry(4_095);
+    ladder.entry(4_096);
+    ladder.entry(19_999);
+
+    assert_eq!(prices(&ladder), vec![63, 64, 4_095, 4_096, 19_999]);
+    ladder.remove(63);
+    ladder.remove(64);
+    assert_eq!(ladder.min_price(), Some(4_095));
+    ladder.remove(19_999);
+    ladder.remove(4_096);
+    assert_eq!(ladder.max_price(), Some(4_095));
+    assert_eq!(ladder.range(4_096, 20_000).count(), 0);
+    assert_eq!(ladder.range(0, 4_095).count(), 1);
+}
+
+#[test]
+fn test_array_ladder_matches_tree_ladder() {
+    let mut array: ArrayLadder = ArrayLadder::new(-500, 500, 2);
+    let mut tree: TreeLadder = TreeLadder::new();
+
+    let mut seed: u64 = 7;
+    for _ in 0..2_000 {
+        seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+        let price: Price = (seed >> 33) as Price % 1_200 - 600;
+        if seed.is_multiple_of(3) {
+            assert_eq!(array.remove(price), tree.remove(price));
+        } else {
+            array.entry(price);
+            tree.entry(price);
+        }
+
+        assert_eq!(array.len(), tree.len());
+        assert_eq!(array.min_price(), tree.min_price());
+        assert_eq!(array.max_price(), tree.max_price());
+    }
+
+    assert_eq!(prices(&array), prices(&tree));
+    assert_eq!(
+        array.range(-100, 301).rev().map(|(price, _)| price).collect::<Vec<_>>(),
+        tree.range(-100, 301).rev().map(|(price, _)| price).collect::<Vec<_>>(),
+    );
+}
+
+#[test]
+fn test_oversized_array_ladder_uses_overflow() {
+    let mut ladder: ArrayLadder = ArrayLadder::new(Price::MIN, Price::MAX, 1);
+    ladder.entry(Price::MIN);
+    ladder.entry(0);
+    ladder.entry(Price::MAX);
+
+    assert_eq!(prices(&ladder), vec![Price::MIN, 0, Price::MAX]);
+    assert_eq!(ladder.min_price(), Some(Price::MIN));
+    assert!(ladder.remove(0).is_some());
+    assert_eq!(ladder.len(), 2);
+}
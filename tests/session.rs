@@ -0,0 +1,34 @@
+use my_order_book::TradingPhase;
+
+#[test]
+fn test_default_phase_is_open() {
+    assert_eq!(TradingPhase::default(), TradingPhase::Open);
+}
+
+#[test]
+fn test_phase_permissions() {
+    assert!(TradingPhase::PreOpen.accepts_orders());
+    assert!(TradingPhase::Open.accepts_orders());
+    assert!(!TradingPhase::Halted.accepts_orders());
+    assert!(!TradingPhase::Closed.accepts_orders());
+
+    assert!(TradingPhase::PreOpen.accepts_cancels());
+    assert!(TradingPhase::Open.accepts_cancels());
+    assert!(TradingPhase::Halted.accepts_cancels());
+    assert!(!TradingPhase::Closed.accepts_cancels());
+}
+
+#[test]
+fn test_phase_transitions() {
+    assert!(TradingPhase::Closed.can_transition_to(TradingPhase::PreOpen));
+    assert!(TradingPhase::PreOpen.can_transition_to(TradingPhase::Open));
+    assert!(TradingPhase::Open.can_transition_to(TradingPhase::Halted));
+    assert!(TradingPhase::Halted.can_transition_to(TradingPhase::Open));
+    assert!(TradingPhase::Halted.can_transition_to(TradingPhase::PreOpen));
+    assert!(TradingPhase::Open.can_transition_to(TradingPhase::Closed));
+
+    assert!(!TradingPhase::Closed.can_transition_to(TradingPhase::Open));
+    assert!(!TradingPhase::Closed.can_transition_to(TradingPhase::Halted));
+    assert!(!TradingPhase::PreOpen.can_transition_to(TradingPhase::Halted));
+    assert!(!TradingPhase::Open.can_transition_to(TradingPhase::PreOpen));
+}
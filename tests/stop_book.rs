@@ -0,0 +1,112 @@
+use my_order_book::{Order, OrdType, Price, TimeInForce, Side, StopBook, TrailAmount};
+
+fn stop_order(id: u64, side: Side, stop_price: Price) -> Order {
+    let mut order: Order = Order::new(id, side, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 10);
+    order.stop_price = Some(stop_price);
+    order
+}
+
+#[test]
+fn test_stop_book_insert_and_remove() {
+    let mut stops: StopBook = StopBook::new();
+    assert!(stops.is_empty());
+
+    stops.insert(stop_order(1, Side::Buy, 105));
+    stops.insert(stop_order(2, Side::Sell, 95));
+    assert_eq!(stops.len(), 2);
+    assert!(stops.contains(1));
+    assert_eq!(stops.get(2).unwrap().get_stop_price(), Some(95));
+
+    let removed = stops.remove(1).unwrap();
+    assert_eq!(removed.get_order_id(), 1);
+    assert!(!stops.contains(1));
+    assert!(stops.remove(1).is_none());
+    assert_eq!(stops.len(), 1);
+}
+
+#[test]
+fn test_stop_book_ignores_orders_without_stop_price() {
+    let mut stops: StopBook = StopBook::new();
+    stops.insert(Order::new(1, Side::Buy, OrdType::Stop, TimeInForce::GoodTillCancel, 0, 10));
+    assert!(stops.is_empty());
+}
+
+#[test]
+fn test_is_triggered() {
+    assert!(StopBook::<Price>::is_triggered(Side::Buy, 105, 105));
+    assert!(StopBook::<Price>::is_triggered(Side::Buy, 105, 106));
+    assert!(!StopBook::<Price>::is_triggered(Side::Buy, 105, 104));
+
+    assert!(StopBook::<Price>::is_triggered(Side::Sell, 95, 95));
+    assert!(StopBook::<Price>::is_triggered(Side::Sell, 95, 94));
+    assert!(!StopBook::<Price>::is_triggered(Side::Sell, 95, 96));
+}
+
+#[test]
+fn test_take_triggered_orders_by_crossing_sequence() {
+    let mut stops: StopBook = StopBook::new();
+    stops.insert(stop_order(1, Side::Buy, 103));
+    stops.insert(stop_order(2, Side::Buy, 101));
+    stops.insert(stop_order(3, Side::Buy, 101));
+    stops.insert(stop_order(4, Side::Buy, 110));
+    stops.insert(stop_order(5, Side::Sell, 90));
+
+    let triggered = stops.take_triggered(105);
+    let ids: Vec<u64> = triggered.iter().map(|o| o.get_order_id()).collect();
+
+    // Lowest buy stop first, FIFO within a stop price
+    assert_eq!(ids, vec![2, 3, 1]);
+    assert_eq!(stops.len(), 2);
+    assert!(stops.contains(4));
+    assert!(stops.contains(5));
+}
+
+#[test]
+fn test_take_triggered_sell_stops_from_highest() {
+    let mut stops: StopBook = StopBook::new();
+    stops.insert(stop_order(1, Side::Sell, 95));
+    stops.insert(stop_order(2, Side::Sell, 98));
+    stops.insert(stop_order(3, Side::Sell, 90));
+
+    let triggered = stops.take_triggered(94);
+    let ids: Vec<u64> = triggered.iter().map(|o| o.get_order_id()).collect();
+
+    assert_eq!(ids, vec![2, 1]);
+    assert!(stops.contains(3));
+}
+
+#[test]
+fn test_ratchet_trailing_stops() {
+    let mut stops: StopBook = StopBook::new();
+
+    let mut trailing = stop_order(1, Side::Sell, 95);
+    trailing.trail = Some(TrailAmount::Absolute(5));
+    stops.insert(trailing);
+    stops.insert(stop_order(2, Side::Sell, 95));
+
+    stops.ratchet_trailing_stops(110, 1);
+    assert_eq!(stops.get(1).unwrap().get_stop_price(), Some(105));
+    assert_eq!(stops.get(2).unwrap().get_stop_price(), Some(95));
+
+    stops.ratchet_trailing_stops(100, 1);
+    assert_eq!(stops.get(1).unwrap().get_stop_price(), Some(105));
+
+    // Moved stops trigger at their new stop price
+    let triggered = stops.take_triggered(105);
+    assert_eq!(triggered.len(), 1);
+    assert_eq!(triggered[0].get_order_id(), 1);
+}
+
+#[test]
+fn test_take_triggered_in_range() {
+    let mut book: StopBook = StopBook::new();
+    book.insert(stop_order(1, Side::Sell, 100));
+    book.insert(stop_order(2, Side::Sell, 98));
+    book.insert(stop_order(3, Side::Buy, 102));
+    book.insert(stop_order(4, Side::Buy, 104));
+
+    // Trades from 99 to 103 cross the sell stop at 100 and the buy stop at 102
+    let ids: Vec<u64> = book.take_triggered_in_range(99, 103).iter().map(|order: &Order| order.get_order_id()).collect();
+    assert_eq!(ids, vec![3, 1]);
+    assert_eq!(book.len(), 2);
+}
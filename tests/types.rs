@@ -1,4 +1,6 @@
-use my_order_book::{Side, OrderType, LevelInfo, OrderStatus, OrderResult, Trade, TradeInfo};
+#![allow(clippy::clone_on_copy, clippy::match_like_matches_macro)]
+
+use my_order_book::{Side, OrdType, TimeInForce, LevelInfo, Price, Quantity, OrderStatus, OrderResult, Trade, TradeInfo};
 
 #[test]
 fn test_side_enum_values() {
@@ -46,37 +48,30 @@ fn test_side_hash() {
 }
 
 #[test]
-fn test_order_type_enum_values() {
-    let limit = OrderType::Limit;
-    let market = OrderType::Market;
-    let fok = OrderType::FillOrKill;
-    let fak = OrderType::FillAndKill;
-    let gtc = OrderType::GoodTillCancel;
-    let gfd = OrderType::GoodForDay;
-    let stop = OrderType::Stop;
-    let stop_limit = OrderType::StopLimit;
+fn test_ord_type_enum_values() {
+    let limit = OrdType::Limit;
+    let market = OrdType::Market;
+    let market_to_limit = OrdType::MarketToLimit;
+    let stop = OrdType::Stop;
+    let stop_limit = OrdType::StopLimit;
     
-    assert_eq!(limit, OrderType::Limit);
-    assert_eq!(market, OrderType::Market);
-    assert_eq!(fok, OrderType::FillOrKill);
-    assert_eq!(fak, OrderType::FillAndKill);
-    assert_eq!(gtc, OrderType::GoodTillCancel);
-    assert_eq!(gfd, OrderType::GoodForDay);
-    assert_eq!(stop, OrderType::Stop);
-    assert_eq!(stop_limit, OrderType::StopLimit);
+    assert_eq!(limit, OrdType::Limit);
+    assert_eq!(market, OrdType::Market);
+    assert_eq!(market_to_limit, OrdType::MarketToLimit);
+    assert_eq!(stop, OrdType::Stop);
+    assert_eq!(stop_limit, OrdType::StopLimit);
 }
 
 #[test]
-fn test_order_type_inequality() {
-    assert_ne!(OrderType::Limit, OrderType::Market);
-    assert_ne!(OrderType::FillOrKill, OrderType::FillAndKill);
-    assert_ne!(OrderType::GoodTillCancel, OrderType::GoodForDay);
-    assert_ne!(OrderType::Stop, OrderType::StopLimit);
+fn test_ord_type_inequality() {
+    assert_ne!(OrdType::Limit, OrdType::Market);
+    assert_ne!(OrdType::Market, OrdType::MarketToLimit);
+    assert_ne!(OrdType::Stop, OrdType::StopLimit);
 }
 
 #[test]
-fn test_order_type_clone_and_copy() {
-    let limit = OrderType::Limit;
+fn test_ord_type_clone_and_copy() {
+    let limit = OrdType::Limit;
     let limit_clone = limit.clone();
     let limit_copy = limit;
     
@@ -86,27 +81,56 @@ fn test_order_type_clone_and_copy() {
 }
 
 #[test]
-fn test_order_type_debug_format() {
-    let order_types = [
-        (OrderType::Limit, "Limit"),
-        (OrderType::Market, "Market"),
-        (OrderType::FillOrKill, "FillOrKill"),
-        (OrderType::FillAndKill, "FillAndKill"),
-        (OrderType::GoodTillCancel, "GoodTillCancel"),
-        (OrderType::GoodForDay, "GoodForDay"),
-        (OrderType::Stop, "Stop"),
-        (OrderType::StopLimit, "StopLimit"),
+fn test_ord_type_debug_format() {
+    let ord_types = [
+        (OrdType::Limit, "Limit"),
+        (OrdType::Market, "Market"),
+        (OrdType::MarketToLimit, "MarketToLimit"),
+        (OrdType::Stop, "Stop"),
+        (OrdType::StopLimit, "StopLimit"),
     ];
     
-    for (order_type, expected_debug) in order_types.iter() {
-        let debug_str = format!("{:?}", order_type);
+    for (ord_type, expected_debug) in ord_types.iter() {
+        let debug_str = format!("{:?}", ord_type);
+        assert_eq!(debug_str, *expected_debug);
+    }
+}
+
+#[test]
+fn test_time_in_force_enum_values() {
+    assert_ne!(TimeInForce::GoodTillCancel, TimeInForce::GoodForDay);
+    assert_ne!(TimeInForce::GoodForDay, TimeInForce::GoodTillDate);
+    assert_ne!(TimeInForce::FillOrKill, TimeInForce::FillAndKill);
+}
+
+#[test]
+fn test_time_in_force_debug_format() {
+    let time_in_forces = [
+        (TimeInForce::GoodTillCancel, "GoodTillCancel"),
+        (TimeInForce::GoodForDay, "GoodForDay"),
+        (TimeInForce::GoodTillDate, "GoodTillDate"),
+        (TimeInForce::FillAndKill, "FillAndKill"),
+        (TimeInForce::FillOrKill, "FillOrKill"),
+    ];
+
+    for (time_in_force, expected_debug) in time_in_forces.iter() {
+        let debug_str = format!("{:?}", time_in_force);
         assert_eq!(debug_str, *expected_debug);
     }
 }
 
+#[test]
+fn test_time_in_force_can_rest() {
+    assert!(TimeInForce::GoodTillCancel.can_rest());
+    assert!(TimeInForce::GoodForDay.can_rest());
+    assert!(TimeInForce::GoodTillDate.can_rest());
+    assert!(!TimeInForce::FillAndKill.can_rest());
+    assert!(!TimeInForce::FillOrKill.can_rest());
+}
+
 #[test]
 fn test_level_info_creation() {
-    let level_info = LevelInfo {
+    let level_info: LevelInfo = LevelInfo {
         price: 100,
         quantity: 50,
     };
@@ -117,7 +141,7 @@ fn test_level_info_creation() {
 
 #[test]
 fn test_level_info_with_zero_values() {
-    let level_info = LevelInfo {
+    let level_info: LevelInfo = LevelInfo {
         price: 0,
         quantity: 0,
     };
@@ -128,18 +152,18 @@ fn test_level_info_with_zero_values() {
 
 #[test]
 fn test_level_info_with_max_values() {
-    let level_info = LevelInfo {
-        price: u32::MAX,
-        quantity: u32::MAX,
+    let level_info: LevelInfo = LevelInfo {
+        price: Price::MAX,
+        quantity: Quantity::MAX,
     };
     
-    assert_eq!(level_info.price, u32::MAX);
-    assert_eq!(level_info.quantity, u32::MAX);
+    assert_eq!(level_info.price, Price::MAX);
+    assert_eq!(level_info.quantity, Quantity::MAX);
 }
 
 #[test]
 fn test_level_info_clone_and_equality() {
-    let level_info1 = LevelInfo {
+    let level_info1: LevelInfo = LevelInfo {
         price: 150,
         quantity: 25,
     };
@@ -152,9 +176,9 @@ fn test_level_info_clone_and_equality() {
 
 #[test]
 fn test_level_info_inequality() {
-    let level_info1 = LevelInfo { price: 100, quantity: 10 };
-    let level_info2 = LevelInfo { price: 105, quantity: 10 };
-    let level_info3 = LevelInfo { price: 100, quantity: 15 };
+    let level_info1: LevelInfo = LevelInfo { price: 100, quantity: 10 };
+    let level_info2: LevelInfo = LevelInfo { price: 105, quantity: 10 };
+    let level_info3: LevelInfo = LevelInfo { price: 100, quantity: 15 };
     
     assert_ne!(level_info1, level_info2); // Different price
     assert_ne!(level_info1, level_info3); // Different quantity
@@ -162,7 +186,7 @@ fn test_level_info_inequality() {
 
 #[test]
 fn test_level_info_debug_format() {
-    let level_info = LevelInfo {
+    let level_info: LevelInfo = LevelInfo {
         price: 123,
         quantity: 456,
     };
@@ -177,12 +201,12 @@ fn test_level_info_debug_format() {
 fn test_type_aliases() {
     // Test that type aliases work correctly
     let order_id: my_order_book::OrderId = 12345u64;
-    let price: my_order_book::Price = 100u32;
-    let quantity: my_order_book::Quantity = 50u32;
+    let price: my_order_book::Price = 100i64;
+    let quantity: my_order_book::Quantity = 50u64;
     
     assert_eq!(order_id, 12345u64);
-    assert_eq!(price, 100u32);
-    assert_eq!(quantity, 50u32);
+    assert_eq!(price, 100i64);
+    assert_eq!(quantity, 50u64);
 }
 
 #[test]
@@ -197,12 +221,12 @@ fn test_order_ids_vec() {
 #[test]
 fn test_realistic_level_info_scenario() {
     // Simulate realistic market data
-    let bid_level = LevelInfo {
+    let bid_level: LevelInfo = LevelInfo {
         price: 9950, // $99.50 in cents
         quantity: 1000,
     };
     
-    let ask_level = LevelInfo {
+    let ask_level: LevelInfo = LevelInfo {
         price: 10000, // $100.00 in cents
         quantity: 500,
     };
@@ -230,19 +254,18 @@ fn test_side_pattern_matching() {
 }
 
 #[test]
-fn test_order_type_pattern_matching() {
-    fn is_immediate_order(order_type: OrderType) -> bool {
-        match order_type {
-            OrderType::Market | OrderType::FillOrKill | OrderType::FillAndKill => true,
+fn test_time_in_force_pattern_matching() {
+    fn is_immediate(time_in_force: TimeInForce) -> bool {
+        match time_in_force {
+            TimeInForce::FillOrKill | TimeInForce::FillAndKill => true,
             _ => false,
         }
     }
     
-    assert!(is_immediate_order(OrderType::Market));
-    assert!(is_immediate_order(OrderType::FillOrKill));
-    assert!(is_immediate_order(OrderType::FillAndKill));
-    assert!(!is_immediate_order(OrderType::Limit));
-    assert!(!is_immediate_order(OrderType::GoodTillCancel));
+    assert!(is_immediate(TimeInForce::FillOrKill));
+    assert!(is_immediate(TimeInForce::FillAndKill));
+    assert!(!is_immediate(TimeInForce::GoodTillCancel));
+    assert!(!is_immediate(TimeInForce::GoodForDay));
 }
 
 #[test]
@@ -299,7 +322,7 @@ fn test_order_result_creation() {
         )
     ];
 
-    let result = OrderResult::new(42, OrderStatus::Executed, trades.clone());
+    let result: OrderResult = OrderResult::new(42, OrderStatus::Executed, trades.clone());
 
     assert_eq!(result.order_id, 42);
     assert_eq!(result.status, OrderStatus::Executed);
@@ -310,7 +333,7 @@ fn test_order_result_creation() {
 
 #[test]
 fn test_order_result_with_no_trades() {
-    let result = OrderResult::new(123, OrderStatus::Accepted, Vec::new());
+    let result: OrderResult = OrderResult::new(123, OrderStatus::Accepted, Vec::new());
 
     assert_eq!(result.order_id, 123);
     assert_eq!(result.status, OrderStatus::Accepted);
@@ -319,21 +342,47 @@ fn test_order_result_with_no_trades() {
 
 #[test]
 fn test_order_result_clone_and_equality() {
-    let result1 = OrderResult::new(1, OrderStatus::Accepted, Vec::new());
+    let result1: OrderResult = OrderResult::new(1, OrderStatus::Accepted, Vec::new());
     let result2 = result1.clone();
 
     assert_eq!(result1, result2);
 
-    let result3 = OrderResult::new(2, OrderStatus::Accepted, Vec::new());
+    let result3: OrderResult = OrderResult::new(2, OrderStatus::Accepted, Vec::new());
     assert_ne!(result1, result3);
 }
 
 #[test]
 fn test_order_result_debug_format() {
-    let result = OrderResult::new(42, OrderStatus::Executed, Vec::new());
+    let result: OrderResult = OrderResult::new(42, OrderStatus::Executed, Vec::new());
     let debug_str = format!("{:?}", result);
 
     assert!(debug_str.contains("OrderResult"));
     assert!(debug_str.contains("42"));
     assert!(debug_str.contains("Executed"));
 }
+
+#[test]
+fn test_trail_amount_distance() {
+    use my_order_book::TrailAmount;
+
+    assert_eq!(TrailAmount::<Price>::Absolute(5).distance(100), 5);
+    assert_eq!(TrailAmount::<Price>::Absolute(5).distance(1_000), 5);
+    assert_eq!(TrailAmount::<Price>::BasisPoints(250).distance(1_000), 25);
+    assert_eq!(TrailAmount::<Price>::BasisPoints(250).distance(10), 0);
+}
+
+#[test]
+fn test_order_status_is_rejected() {
+    assert!(!OrderStatus::Accepted.is_rejected());
+    assert!(!OrderStatus::Executed.is_rejected());
+    assert!(!OrderStatus::PendingTrigger.is_rejected());
+    assert!(!OrderStatus::Repriced.is_rejected());
+    assert!(OrderStatus::RejectedNoLiquidity.is_rejected());
+    assert!(OrderStatus::RejectedSideChange.is_rejected());
+}
+
+#[test]
+fn test_side_opposite() {
+    assert_eq!(Side::Buy.opposite(), Side::Sell);
+    assert_eq!(Side::Sell.opposite(), Side::Buy);
+}